@@ -0,0 +1,440 @@
+//! A multi-producer, multi-consumer broadcast channel.
+//!
+//! Every message sent on a broadcast channel is observed by every
+//! [`Receiver`] that exists at the time the message is sent. Messages are kept
+//! in a fixed-capacity ring buffer shared by all receivers, so sending never
+//! waits: once the buffer is full, the oldest message is overwritten.
+//!
+//! A receiver that falls so far behind that messages it has not yet seen get
+//! overwritten is said to have *lagged*. Its next receive returns
+//! [`RecvError::Lagged`] with the number of messages it missed, after which it
+//! continues with the oldest message still retained by the channel.
+//!
+//! # Disconnection
+//!
+//! When all [`Sender`] handles have been dropped, receivers yield the messages
+//! remaining in the buffer and then terminate. Once all receivers have been
+//! dropped, sending fails and returns the message in a [`SendError`].
+//!
+//! [`Sender`]: struct.Sender.html
+//! [`Receiver`]: struct.Receiver.html
+//! [`SendError`]: struct.SendError.html
+//! [`RecvError::Lagged`]: enum.RecvError.html#variant.Lagged
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll, Waker};
+use std::any::Any;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// The transmission end of a broadcast channel.
+///
+/// This value is created by the [`channel`](channel) function.
+#[derive(Debug)]
+pub struct Sender<T>(Option<Arc<Mutex<State<T>>>>);
+
+/// The receiving end of a broadcast channel.
+///
+/// This value is created by the [`channel`](channel) function or by
+/// [`Sender::subscribe`](Sender::subscribe). Cloning a receiver creates a new
+/// receiver which will observe the same messages as the original from its
+/// current position onwards.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    inner: Arc<Mutex<State<T>>>,
+    // Identifies this receiver's entry in `State::recv_tasks`.
+    id: usize,
+    // Sequence number of the next message this receiver will observe.
+    next: u64,
+    terminated: bool,
+}
+
+// The channel does not ever project Pin to the inner T
+impl<T> Unpin for Sender<T> {}
+impl<T> Unpin for Receiver<T> {}
+
+/// The error type returned when sending on a channel with no receivers.
+///
+/// The message that failed to send is returned.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+/// The error type yielded by a [`Receiver`](Receiver) used as a `Stream`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecvError {
+    /// The receiver lagged too far behind and the given number of messages
+    /// were overwritten before it could observe them.
+    Lagged(u64),
+}
+
+/// The error type returned from [`try_next`](Receiver::try_next).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// There are currently no messages available to this receiver.
+    Empty,
+    /// The receiver lagged too far behind and the given number of messages
+    /// were overwritten before it could observe them.
+    Lagged(u64),
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("SendError")
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "send failed because all receivers are gone")
+    }
+}
+
+impl<T: Any> Error for SendError<T> {
+    fn description(&self) -> &str {
+        "send failed because all receivers are gone"
+    }
+}
+
+impl<T> SendError<T> {
+    /// Returns the message that was attempted to be sent but failed.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Lagged(n) => write!(fmt, "receiver lagged by {} messages", n),
+        }
+    }
+}
+
+impl Error for RecvError {
+    fn description(&self) -> &str {
+        "receiver lagged behind"
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(fmt, "receiver channel is empty"),
+            TryRecvError::Lagged(n) => write!(fmt, "receiver lagged by {} messages", n),
+        }
+    }
+}
+
+impl Error for TryRecvError {
+    fn description(&self) -> &str {
+        match self {
+            TryRecvError::Empty => "receiver channel is empty",
+            TryRecvError::Lagged(_) => "receiver lagged behind",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State<T> {
+    // Ring buffer holding the most recent `capacity` messages.
+    buffer: VecDeque<T>,
+    capacity: usize,
+
+    // Sequence number of the message at the front of `buffer`.
+    head: u64,
+
+    num_senders: usize,
+    num_receivers: usize,
+
+    // Id handed out to the next receiver created.
+    next_id: usize,
+
+    // Tasks blocked on a receiver waiting for a new message, keyed by
+    // receiver id.
+    recv_tasks: Vec<(usize, Waker)>,
+}
+
+impl<T> State<T> {
+    // Sequence number that will be assigned to the next message sent.
+    fn tail(&self) -> u64 {
+        self.head + self.buffer.len() as u64
+    }
+
+    fn new_receiver(&mut self, inner: Arc<Mutex<State<T>>>, next: u64) -> Receiver<T> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.num_receivers += 1;
+        Receiver {
+            inner,
+            id,
+            next,
+            terminated: false,
+        }
+    }
+
+    fn register(&mut self, id: usize, lw: &LocalWaker) {
+        match self.recv_tasks.iter_mut().find(|(task_id, _)| *task_id == id) {
+            Some((_, task)) => {
+                if !task.will_wake_nonlocal(lw) {
+                    *task = lw.clone().into_waker();
+                }
+            }
+            None => self.recv_tasks.push((id, lw.clone().into_waker())),
+        }
+    }
+
+    fn take_recv_tasks(&mut self) -> Vec<(usize, Waker)> {
+        mem::replace(&mut self.recv_tasks, Vec::new())
+    }
+}
+
+fn wake_all(tasks: Vec<(usize, Waker)>) {
+    for (_, task) in tasks {
+        task.wake();
+    }
+}
+
+/// Creates a broadcast channel which retains up to `capacity` messages for
+/// its receivers.
+///
+/// Every message sent is observed by every receiver, so `T` has to be `Clone`
+/// for the receivers to be usable. Further receivers can be created with
+/// [`Sender::subscribe`](Sender::subscribe) or by cloning an existing
+/// [`Receiver`](Receiver).
+///
+/// The [`Receiver`](Receiver) returned implements the
+/// [`Stream`](futures_core::stream::Stream) trait, while [`Sender`](Sender)
+/// implements `Sink`.
+///
+/// # Panics
+///
+/// Panics if `capacity` is `0`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::broadcast;
+/// use futures::executor::block_on_stream;
+///
+/// let (tx, rx1) = broadcast::channel(16);
+/// let rx2 = tx.subscribe();
+///
+/// tx.send(1).unwrap();
+/// tx.send(2).unwrap();
+/// drop(tx);
+///
+/// let v1: Vec<_> = block_on_stream(rx1).map(Result::unwrap).collect();
+/// let v2: Vec<_> = block_on_stream(rx2).map(Result::unwrap).collect();
+/// assert_eq!(v1, vec![1, 2]);
+/// assert_eq!(v2, vec![1, 2]);
+/// ```
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast capacity must be greater than zero");
+    let inner = Arc::new(Mutex::new(State {
+        buffer: VecDeque::with_capacity(capacity),
+        capacity,
+        head: 0,
+        num_senders: 1,
+        num_receivers: 0,
+        next_id: 0,
+        recv_tasks: Vec::new(),
+    }));
+    let rx = inner.lock().unwrap().new_receiver(inner.clone(), 0);
+    (Sender(Some(inner)), rx)
+}
+
+/*
+ *
+ * ===== impl Sender =====
+ *
+ */
+
+impl<T> Sender<T> {
+    /// Sends a message to all current receivers.
+    ///
+    /// This never waits: if the channel is at capacity the oldest message is
+    /// overwritten, and receivers which have not yet observed it will report
+    /// that they lagged behind.
+    ///
+    /// Returns the message in a [`SendError`](SendError) if there are no
+    /// receivers left or this sender has been disconnected.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        let inner = match &self.0 {
+            Some(inner) => inner,
+            None => return Err(SendError(msg)),
+        };
+        let tasks = {
+            let mut state = inner.lock().unwrap();
+            if state.num_receivers == 0 {
+                return Err(SendError(msg));
+            }
+            if state.buffer.len() == state.capacity {
+                state.buffer.pop_front();
+                state.head += 1;
+            }
+            state.buffer.push_back(msg);
+            state.take_recv_tasks()
+        };
+        wake_all(tasks);
+        Ok(())
+    }
+
+    /// Creates a new receiver which will observe all messages sent after
+    /// this call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this sender has been disconnected.
+    pub fn subscribe(&self) -> Receiver<T> {
+        let inner = self.0.as_ref().expect("subscribe called on a disconnected sender");
+        let mut state = inner.lock().unwrap();
+        let next = state.tail();
+        state.new_receiver(inner.clone(), next)
+    }
+
+    /// Returns the number of receivers currently subscribed to this channel.
+    pub fn receiver_count(&self) -> usize {
+        self.0.as_ref().map(|inner| inner.lock().unwrap().num_receivers).unwrap_or(0)
+    }
+
+    /// Returns whether this channel is closed, which is the case once all
+    /// receivers have been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+
+    /// Disconnects this sender from the channel, terminating the receivers if
+    /// there are no more senders left.
+    pub fn disconnect(&mut self) {
+        if let Some(inner) = self.0.take() {
+            let tasks = {
+                let mut state = inner.lock().unwrap();
+                state.num_senders -= 1;
+                if state.num_senders == 0 {
+                    state.take_recv_tasks()
+                } else {
+                    Vec::new()
+                }
+            };
+            wake_all(tasks);
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        if let Some(inner) = &self.0 {
+            inner.lock().unwrap().num_senders += 1;
+        }
+        Sender(self.0.clone())
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/*
+ *
+ * ===== impl Receiver =====
+ *
+ */
+
+impl<T: Clone> Receiver<T> {
+    /// Tries to receive the next message without notifying a context if empty.
+    ///
+    /// Returns `Ok(None)` once all senders have been dropped and every
+    /// remaining message has been observed.
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        if self.terminated {
+            return Ok(None);
+        }
+        let mut state = self.inner.lock().unwrap();
+        match next_message(&mut state, &mut self.next, &mut self.terminated) {
+            Poll::Ready(Some(Ok(msg))) => Ok(Some(msg)),
+            Poll::Ready(Some(Err(RecvError::Lagged(n)))) => Err(TryRecvError::Lagged(n)),
+            Poll::Ready(None) => Ok(None),
+            Poll::Pending => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+fn next_message<T: Clone>(
+    state: &mut State<T>,
+    next: &mut u64,
+    terminated: &mut bool,
+) -> Poll<Option<Result<T, RecvError>>> {
+    if *next < state.head {
+        // Messages this receiver has not seen were overwritten, so skip
+        // ahead to the oldest message that is still available.
+        let missed = state.head - *next;
+        *next = state.head;
+        return Poll::Ready(Some(Err(RecvError::Lagged(missed))));
+    }
+
+    let index = (*next - state.head) as usize;
+    match state.buffer.get(index) {
+        Some(msg) => {
+            *next += 1;
+            Poll::Ready(Some(Ok(msg.clone())))
+        }
+        None if state.num_senders == 0 => {
+            *terminated = true;
+            Poll::Ready(None)
+        }
+        None => Poll::Pending,
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        let mut state = self.inner.lock().unwrap();
+        let mut rx = state.new_receiver(self.inner.clone(), self.next);
+        rx.terminated = self.terminated;
+        rx
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Clone> Stream for Receiver<T> {
+    type Item = Result<T, RecvError>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<Option<Self::Item>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+        let Receiver { inner, id, next, terminated } = &mut *self;
+        let mut state = inner.lock().unwrap();
+        let res = next_message(&mut state, next, terminated);
+        if res.is_pending() {
+            // Senders take the lock before publishing a message, so
+            // registering while still holding it can't miss a wakeup.
+            state.register(*id, lw);
+        }
+        res
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut state = self.inner.lock().unwrap();
+        state.num_receivers -= 1;
+        let id = self.id;
+        state.recv_tasks.retain(|(task_id, _)| *task_id != id);
+    }
+}
@@ -4,6 +4,7 @@
 //! asynchronous tasks.
 
 #![feature(futures_api)]
+#![cfg_attr(feature = "cfg-target-has-atomic", feature(cfg_target_has_atomic))]
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -11,9 +12,23 @@
 
 #![doc(html_root_url = "https://rust-lang-nursery.github.io/futures-api-docs/0.3.0-alpha.12/futures_channel")]
 
+#[cfg(all(feature = "cfg-target-has-atomic", not(feature = "nightly")))]
+compile_error!("The `cfg-target-has-atomic` feature requires the `nightly` feature as an explicit opt-in to unstable features");
+
+#[cfg(feature = "std")]
+pub mod broadcast;
 #[cfg(feature = "std")]
 mod lock;
 #[cfg(feature = "std")]
+pub mod mpmc;
+#[cfg(feature = "std")]
 pub mod mpsc;
 #[cfg(feature = "std")]
 pub mod oneshot;
+#[cfg_attr(
+    feature = "cfg-target-has-atomic",
+    cfg(all(target_has_atomic = "cas", target_has_atomic = "ptr"))
+)]
+pub mod oneshot_slot;
+#[cfg(feature = "std")]
+pub mod watch;
@@ -0,0 +1,445 @@
+//! A bounded multi-producer, multi-consumer queue for distributing work
+//! across asynchronous tasks.
+//!
+//! This channel behaves like a bounded [`mpsc`](crate::mpsc) channel, except
+//! that the [`Receiver`] can be cloned. Each message is delivered to exactly
+//! one of the receivers, whichever gets to it first, which makes the channel
+//! suitable for handing jobs to a pool of worker tasks.
+//!
+//! Backpressure works the same way as for [`mpsc::channel`]: the capacity of
+//! the channel is `buffer + num-senders`, each sender getting a guaranteed
+//! slot, and a sender that exceeds the buffer is parked until a receiver takes
+//! a message out of the channel.
+//!
+//! # Disconnection
+//!
+//! When all [`Sender`] handles have been dropped, receivers drain the
+//! remaining messages and then terminate. When all [`Receiver`] handles have
+//! been dropped, or one of them calls [`close`](Receiver::close), further
+//! sends fail.
+//!
+//! [`Sender`]: struct.Sender.html
+//! [`Receiver`]: struct.Receiver.html
+//! [`mpsc::channel`]: ../mpsc/fn.channel.html
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll, Waker};
+use std::collections::VecDeque;
+use std::mem;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use crate::mpsc::SenderTask;
+
+pub use crate::mpsc::{SendError, TryRecvError, TrySendError};
+
+/// The transmission end of a bounded mpmc channel.
+///
+/// This value is created by the [`channel`](channel) function.
+#[derive(Debug)]
+pub struct Sender<T> {
+    inner: Option<Arc<Mutex<State<T>>>>,
+
+    // Handle to the task that is blocked on this sender. This handle is
+    // pushed onto `State::parked_queue` in order to be notified when the
+    // sender becomes unblocked.
+    sender_task: Arc<Mutex<SenderTask>>,
+}
+
+/// The receiving end of a bounded mpmc channel.
+///
+/// This value is created by the [`channel`](channel) function. Cloning a
+/// receiver creates another handle which competes with the original for
+/// messages.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    inner: Arc<Mutex<State<T>>>,
+    // Identifies this receiver's entry in `State::recv_tasks`.
+    id: usize,
+    terminated: bool,
+}
+
+// The channel does not ever project Pin to the inner T
+impl<T> Unpin for Sender<T> {}
+impl<T> Unpin for Receiver<T> {}
+
+#[derive(Debug)]
+struct State<T> {
+    // Number of messages which can be buffered before senders are parked.
+    buffer: usize,
+
+    // `false` once the channel has been closed by a receiver or all
+    // receivers are gone.
+    is_open: bool,
+
+    messages: VecDeque<T>,
+
+    // Senders which exceeded the buffer, waiting for messages to be taken.
+    parked_queue: VecDeque<Arc<Mutex<SenderTask>>>,
+
+    // Tasks blocked on a receiver waiting for a message, keyed by receiver id.
+    recv_tasks: VecDeque<(usize, Waker)>,
+
+    num_senders: usize,
+    num_receivers: usize,
+
+    // Id handed out to the next receiver created.
+    next_id: usize,
+}
+
+impl<T> State<T> {
+    fn new_receiver(&mut self, inner: Arc<Mutex<State<T>>>) -> Receiver<T> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.num_receivers += 1;
+        Receiver {
+            inner,
+            id,
+            terminated: false,
+        }
+    }
+
+    // Take the task handle of one receiver waiting for a message.
+    fn take_recv_task(&mut self) -> Option<Waker> {
+        self.recv_tasks.pop_front().map(|(_, task)| task)
+    }
+
+    fn take_recv_tasks(&mut self) -> VecDeque<(usize, Waker)> {
+        mem::replace(&mut self.recv_tasks, VecDeque::new())
+    }
+
+    // Clear the `is_open` flag and unpark all senders so that they observe
+    // the channel being closed.
+    fn close(&mut self) {
+        self.is_open = false;
+        while let Some(task) = self.parked_queue.pop_front() {
+            task.lock().unwrap().notify();
+        }
+    }
+}
+
+/// Creates a bounded mpmc channel for distributing messages between
+/// asynchronous tasks.
+///
+/// Being bounded, this channel provides backpressure to ensure that the
+/// senders outpace the receivers by only a limited amount. The channel's
+/// capacity is equal to `buffer + num-senders`, exactly as for
+/// [`mpsc::channel`](crate::mpsc::channel).
+///
+/// The [`Receiver`](Receiver) returned implements the
+/// [`Stream`](futures_core::stream::Stream) trait and can be cloned to
+/// consume messages from several tasks, while [`Sender`](Sender) implements
+/// `Sink`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::mpmc;
+/// use futures::executor::block_on_stream;
+///
+/// let (mut tx, rx1) = mpmc::channel(4);
+/// let rx2 = rx1.clone();
+///
+/// tx.try_send(1).unwrap();
+/// tx.try_send(2).unwrap();
+/// drop(tx);
+///
+/// let mut rx1 = block_on_stream(rx1);
+/// let mut rx2 = block_on_stream(rx2);
+/// assert_eq!(rx1.next(), Some(1));
+/// assert_eq!(rx2.next(), Some(2));
+/// assert_eq!(rx1.next(), None);
+/// assert_eq!(rx2.next(), None);
+/// ```
+pub fn channel<T>(buffer: usize) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Mutex::new(State {
+        buffer,
+        is_open: true,
+        messages: VecDeque::new(),
+        parked_queue: VecDeque::new(),
+        recv_tasks: VecDeque::new(),
+        num_senders: 1,
+        num_receivers: 0,
+        next_id: 0,
+    }));
+    let rx = inner.lock().unwrap().new_receiver(inner.clone());
+    let tx = Sender {
+        inner: Some(inner),
+        sender_task: Arc::new(Mutex::new(SenderTask::new())),
+    };
+    (tx, rx)
+}
+
+/*
+ *
+ * ===== impl Sender =====
+ *
+ */
+
+impl<T> Sender<T> {
+    /// Attempts to send a message on this `Sender`, returning the message
+    /// if there was an error.
+    pub fn try_send(&mut self, msg: T) -> Result<(), TrySendError<T>> {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return Err(TrySendError::new(SendError::disconnected(), msg)),
+        };
+
+        let task = {
+            let mut state = inner.lock().unwrap();
+            if !state.is_open {
+                return Err(TrySendError::new(SendError::disconnected(), msg));
+            }
+
+            // If the sender is currently blocked, reject the message
+            if self.sender_task.lock().unwrap().is_parked {
+                return Err(TrySendError::new(SendError::full(), msg));
+            }
+
+            state.messages.push_back(msg);
+
+            // Block if the number of pending messages has exceeded the
+            // configured buffer size. The message is still accepted as it
+            // occupies this sender's guaranteed slot.
+            if state.messages.len() > state.buffer {
+                let mut sender = self.sender_task.lock().unwrap();
+                sender.task = None;
+                sender.is_parked = true;
+                drop(sender);
+                state.parked_queue.push_back(self.sender_task.clone());
+            }
+
+            state.take_recv_task()
+        };
+
+        if let Some(task) = task {
+            task.wake();
+        }
+        Ok(())
+    }
+
+    /// Send a message on the channel.
+    ///
+    /// This function should only be called after
+    /// [`poll_ready`](Sender::poll_ready) has reported that the channel is
+    /// ready to receive a message.
+    pub fn start_send(&mut self, msg: T) -> Result<(), SendError> {
+        self.try_send(msg)
+            .map_err(|e| e.into_send_error())
+    }
+
+    /// Polls the channel to determine if there is guaranteed capacity to send
+    /// at least one item without waiting.
+    ///
+    /// # Return value
+    ///
+    /// This method returns:
+    ///
+    /// - `Ok(Async::Ready(_))` if there is sufficient capacity;
+    /// - `Ok(Async::Pending)` if the channel may not have
+    ///   capacity, in which case the current task is queued to be notified once
+    ///   capacity is available;
+    /// - `Err(SendError)` if all receivers have been dropped.
+    pub fn poll_ready(
+        &mut self,
+        lw: &LocalWaker
+    ) -> Poll<Result<(), SendError>> {
+        let inner = self.inner.as_ref().ok_or_else(SendError::disconnected)?;
+        let state = inner.lock().unwrap();
+        if !state.is_open {
+            return Poll::Ready(Err(SendError::disconnected()));
+        }
+
+        let mut task = self.sender_task.lock().unwrap();
+        if task.is_parked {
+            // Update the task in case the `Sender` has been moved to another
+            // task
+            task.task = Some(lw.clone().into_waker());
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Returns whether this channel is closed without needing a context.
+    pub fn is_closed(&self) -> bool {
+        self.inner.as_ref()
+            .map(|inner| !inner.lock().unwrap().is_open)
+            .unwrap_or(true)
+    }
+
+    /// Closes this channel from the sender side, preventing any new messages.
+    pub fn close_channel(&mut self) {
+        if let Some(inner) = &self.inner {
+            let tasks = {
+                let mut state = inner.lock().unwrap();
+                state.close();
+                state.take_recv_tasks()
+            };
+            for (_, task) in tasks {
+                task.wake();
+            }
+        }
+    }
+
+    /// Disconnects this sender from the channel, closing it if there are no more senders left.
+    pub fn disconnect(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            let tasks = {
+                let mut state = inner.lock().unwrap();
+                state.num_senders -= 1;
+                if state.num_senders == 0 {
+                    state.close();
+                    state.take_recv_tasks()
+                } else {
+                    VecDeque::new()
+                }
+            };
+            for (_, task) in tasks {
+                task.wake();
+            }
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        if let Some(inner) = &self.inner {
+            inner.lock().unwrap().num_senders += 1;
+        }
+        Sender {
+            inner: self.inner.clone(),
+            sender_task: Arc::new(Mutex::new(SenderTask::new())),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/*
+ *
+ * ===== impl Receiver =====
+ *
+ */
+
+impl<T> Receiver<T> {
+    /// Closes the channel for all receivers, without dropping this one.
+    ///
+    /// This prevents any further messages from being sent on the channel while
+    /// still enabling the receivers to drain messages that are buffered.
+    pub fn close(&mut self) {
+        let tasks = {
+            let mut state = self.inner.lock().unwrap();
+            state.close();
+            // Receivers waiting on an empty queue will now see its end.
+            state.take_recv_tasks()
+        };
+        for (_, task) in tasks {
+            task.wake();
+        }
+    }
+
+    /// Tries to receive the next message without notifying a context if empty.
+    ///
+    /// It is not recommended to call this function from inside of a future,
+    /// only when you've otherwise arranged to be notified when the channel is
+    /// no longer empty.
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        match self.next_message(None) {
+            Poll::Ready(msg) => Ok(msg),
+            Poll::Pending => Err(TryRecvError::new()),
+        }
+    }
+
+    fn next_message(&mut self, lw: Option<&LocalWaker>) -> Poll<Option<T>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        let mut state = self.inner.lock().unwrap();
+        match state.messages.pop_front() {
+            Some(msg) => {
+                // If there are any parked senders, unpark one now that a slot
+                // has been freed.
+                if let Some(task) = state.parked_queue.pop_front() {
+                    task.lock().unwrap().notify();
+                }
+                Poll::Ready(Some(msg))
+            }
+            None if !state.is_open => {
+                // Once closed no more messages can be sent, so an empty
+                // queue is the end of the stream.
+                drop(state);
+                self.terminated = true;
+                Poll::Ready(None)
+            }
+            None => {
+                if let Some(lw) = lw {
+                    let id = self.id;
+                    match state.recv_tasks.iter_mut().find(|(task_id, _)| *task_id == id) {
+                        Some((_, task)) => *task = lw.clone().into_waker(),
+                        None => state.recv_tasks.push_back((id, lw.clone().into_waker())),
+                    }
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        let mut rx = self.inner.lock().unwrap().new_receiver(self.inner.clone());
+        rx.terminated = self.terminated;
+        rx
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<Option<T>> {
+        self.next_message(Some(lw))
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let (messages, task) = {
+            let mut state = self.inner.lock().unwrap();
+            let id = self.id;
+            state.recv_tasks.retain(|(task_id, _)| *task_id != id);
+            state.num_receivers -= 1;
+
+            if state.num_receivers == 0 {
+                // Nobody is left to receive, so close the channel and drop
+                // any pending messages.
+                state.close();
+                (mem::replace(&mut state.messages, VecDeque::new()), None)
+            } else if !state.messages.is_empty() {
+                // This receiver may have been woken for a message it will now
+                // never take, so pass the notification on.
+                (VecDeque::new(), state.take_recv_task())
+            } else {
+                (VecDeque::new(), None)
+            }
+        };
+        drop(messages);
+        if let Some(task) = task {
+            task.wake();
+        }
+    }
+}
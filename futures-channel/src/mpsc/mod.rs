@@ -10,7 +10,9 @@
 //! the task will be notified when additional capacity is available. In other
 //! words, the channel provides backpressure.
 //!
-//! Unbounded channels are also available using the `unbounded` constructor.
+//! Unbounded channels are also available using the `unbounded` constructor,
+//! and bounded channels which deliver messages by priority rather than in
+//! FIFO order using the `priority_channel` constructor.
 //!
 //! # Disconnection
 //!
@@ -92,8 +94,17 @@ use std::usize;
 
 use crate::mpsc::queue::Queue;
 
+mod priority;
+pub use self::priority::{priority_channel, PriorityReceiver, PrioritySender};
+
 mod queue;
 
+mod recv_many;
+pub use self::recv_many::RecvMany;
+
+mod reserve;
+pub use self::reserve::{Permit, Reserve};
+
 #[derive(Debug)]
 struct SenderInner<T> {
     // Channel state shared between the sender and receiver.
@@ -189,6 +200,18 @@ impl Error for SendError {
 }
 
 impl SendError {
+    pub(crate) fn full() -> SendError {
+        SendError {
+            kind: SendErrorKind::Full,
+        }
+    }
+
+    pub(crate) fn disconnected() -> SendError {
+        SendError {
+            kind: SendErrorKind::Disconnected,
+        }
+    }
+
     /// Returns true if this error is a result of the channel being full.
     pub fn is_full(&self) -> bool {
         match self.kind {
@@ -235,6 +258,10 @@ impl<T: Any> Error for TrySendError<T> {
 }
 
 impl<T> TrySendError<T> {
+    pub(crate) fn new(err: SendError, val: T) -> TrySendError<T> {
+        TrySendError { err, val }
+    }
+
     /// Returns true if this error is a result of the channel being full.
     pub fn is_full(&self) -> bool {
         self.err.is_full()
@@ -256,6 +283,12 @@ impl<T> TrySendError<T> {
     }
 }
 
+impl TryRecvError {
+    pub(crate) fn new() -> TryRecvError {
+        TryRecvError { _inner: () }
+    }
+}
+
 impl fmt::Debug for TryRecvError {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_tuple("TryRecvError")
@@ -323,20 +356,20 @@ const MAX_BUFFER: usize = MAX_CAPACITY >> 1;
 
 // Sent to the consumer to wake up blocked producers
 #[derive(Debug)]
-struct SenderTask {
-    task: Option<Waker>,
-    is_parked: bool,
+pub(crate) struct SenderTask {
+    pub(crate) task: Option<Waker>,
+    pub(crate) is_parked: bool,
 }
 
 impl SenderTask {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         SenderTask {
             task: None,
             is_parked: false,
         }
     }
 
-    fn notify(&mut self) {
+    pub(crate) fn notify(&mut self) {
         self.is_parked = false;
 
         if let Some(task) = self.task.take() {
@@ -657,6 +690,37 @@ impl<T> Sender<T> {
         inner.poll_ready(lw)
     }
 
+    /// Creates a future which resolves once there is capacity to send a
+    /// message on this `Sender`.
+    ///
+    /// The returned [`Permit`](Permit) guarantees that the next message sent
+    /// through it is accepted without waiting. This is useful when the
+    /// message is expensive to produce and should only be created once the
+    /// channel can take it.
+    ///
+    /// The future resolves to a [`SendError`](SendError) if the receiver has
+    /// been dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::channel::mpsc;
+    /// use futures::stream::StreamExt;
+    ///
+    /// let (mut tx, mut rx) = mpsc::channel(0);
+    ///
+    /// let permit = await!(tx.reserve()).unwrap();
+    /// permit.send("expensive").unwrap();
+    ///
+    /// assert_eq!(await!(rx.next()), Some("expensive"));
+    /// # });
+    /// ```
+    pub fn reserve(&mut self) -> Reserve<'_, T> {
+        Reserve::new(self)
+    }
+
     /// Returns whether this channel is closed without needing a context.
     pub fn is_closed(&self) -> bool {
         self.0.as_ref().map(SenderInner::is_closed).unwrap_or(true)
@@ -673,6 +737,35 @@ impl<T> Sender<T> {
     pub fn disconnect(&mut self) {
         self.0 = None;
     }
+
+    /// Returns the number of messages currently queued in the channel.
+    ///
+    /// Returns `0` if this sender has been disconnected.
+    pub fn len(&self) -> usize {
+        self.0.as_ref().map(|inner| inner.inner.len()).unwrap_or(0)
+    }
+
+    /// Returns whether there are no messages queued in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of messages the channel can hold, which is
+    /// the `buffer` it was created with plus one guaranteed slot for each
+    /// sender.
+    ///
+    /// Returns `None` if this sender has been disconnected, as well as for
+    /// every [`UnboundedSender`](UnboundedSender::capacity).
+    pub fn capacity(&self) -> Option<usize> {
+        self.0.as_ref().and_then(|inner| inner.inner.capacity())
+    }
+
+    /// Returns the number of senders connected to the channel.
+    ///
+    /// Returns `0` if this sender has been disconnected.
+    pub fn sender_count(&self) -> usize {
+        self.0.as_ref().map(|inner| inner.inner.sender_count()).unwrap_or(0)
+    }
 }
 
 impl<T> UnboundedSender<T> {
@@ -704,6 +797,34 @@ impl<T> UnboundedSender<T> {
         self.0 = None;
     }
 
+    /// Returns the number of messages currently queued in the channel.
+    ///
+    /// Returns `0` if this sender has been disconnected.
+    pub fn len(&self) -> usize {
+        self.0.as_ref().map(|inner| inner.inner.len()).unwrap_or(0)
+    }
+
+    /// Returns whether there are no messages queued in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `None`, as an unbounded channel can hold any number of
+    /// messages.
+    ///
+    /// This mirrors [`Sender::capacity`](Sender::capacity), so that both kinds
+    /// of channel can be inspected the same way.
+    pub fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the number of senders connected to the channel.
+    ///
+    /// Returns `0` if this sender has been disconnected.
+    pub fn sender_count(&self) -> usize {
+        self.0.as_ref().map(|inner| inner.inner.sender_count()).unwrap_or(0)
+    }
+
     // Do the send without parking current task.
     fn do_send_nb(&self, msg: T) -> Result<(), TrySendError<T>> {
         if let Some(inner) = &self.0 {
@@ -836,6 +957,112 @@ impl<T> Receiver<T> {
         }
     }
 
+    /// Returns the number of messages currently queued in the channel.
+    ///
+    /// Returns `0` once the stream has terminated.
+    pub fn len(&self) -> usize {
+        self.inner.as_ref().map(|inner| inner.len()).unwrap_or(0)
+    }
+
+    /// Returns whether there are no messages queued in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of messages the channel can hold, which is
+    /// the `buffer` it was created with plus one guaranteed slot for each
+    /// sender.
+    ///
+    /// Returns `None` once the stream has terminated, as well as for every
+    /// [`UnboundedReceiver`](UnboundedReceiver::capacity).
+    pub fn capacity(&self) -> Option<usize> {
+        self.inner.as_ref().and_then(|inner| inner.capacity())
+    }
+
+    /// Returns the number of senders connected to the channel.
+    ///
+    /// Returns `0` once the stream has terminated.
+    pub fn sender_count(&self) -> usize {
+        self.inner.as_ref().map(|inner| inner.sender_count()).unwrap_or(0)
+    }
+
+    /// Receives up to `max` queued messages in one pass, appending them to
+    /// `buf`.
+    ///
+    /// Returns `Ready(n)` with the number of messages received, or `Pending`
+    /// if the channel is empty, in which case the current task is scheduled
+    /// to be notified when a message arrives. A parked sender is woken for
+    /// each message received.
+    ///
+    /// `Ready(0)` is returned once all senders have been dropped and every
+    /// message has been received, or immediately if `max` is `0`.
+    pub fn poll_recv_many(
+        &mut self,
+        lw: &LocalWaker,
+        buf: &mut Vec<T>,
+        max: usize,
+    ) -> Poll<usize> {
+        if max == 0 {
+            return Poll::Ready(0);
+        }
+        match self.next_messages(buf, max) {
+            Poll::Ready(n) => Poll::Ready(n),
+            Poll::Pending => {
+                // There are no messages to read, in this case, park.
+                self.inner.as_ref().unwrap().recv_task.register(lw);
+                // Check queue again after parking to prevent race condition:
+                // a message could be added to the queue after previous
+                // `next_messages` before `register` call.
+                self.next_messages(buf, max)
+            }
+        }
+    }
+
+    /// Creates a future which receives up to `max` queued messages into
+    /// `buf`, resolving to the number of messages received.
+    ///
+    /// See [`poll_recv_many`](Receiver::poll_recv_many) for details.
+    pub fn recv_many<'a>(&'a mut self, buf: &'a mut Vec<T>, max: usize) -> RecvMany<'a, T> {
+        RecvMany::new(self, buf, max)
+    }
+
+    fn next_messages(&mut self, buf: &mut Vec<T>, max: usize) -> Poll<usize> {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return Poll::Ready(0),
+        };
+
+        let mut received = 0;
+        while received < max {
+            match unsafe { inner.message_queue.pop_spin() } {
+                Some(msg) => {
+                    buf.push(msg);
+                    received += 1;
+
+                    // Unpark a sender for each freed slot.
+                    if let Some(task) = unsafe { inner.parked_queue.pop_spin() } {
+                        task.lock().unwrap().notify();
+                    }
+                }
+                None => break,
+            }
+        }
+
+        if received > 0 {
+            // Decrement number of messages for the whole batch at once.
+            inner.state.fetch_sub(received, SeqCst);
+            return Poll::Ready(received);
+        }
+
+        let state = decode_state(inner.state.load(SeqCst));
+        if state.is_open || state.num_messages != 0 {
+            Poll::Pending
+        } else {
+            self.inner = None;
+            Poll::Ready(0)
+        }
+    }
+
     fn next_message(&mut self) -> Poll<Option<T>> {
         let inner = self.inner.as_mut().expect("Receiver::next_message called after `None`");
         // Pop off a message
@@ -958,6 +1185,54 @@ impl<T> UnboundedReceiver<T> {
     pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
         self.0.try_next()
     }
+
+    /// Receives up to `max` queued messages in one pass, appending them to
+    /// `buf`.
+    ///
+    /// See [`Receiver::poll_recv_many`](Receiver::poll_recv_many) for
+    /// details.
+    pub fn poll_recv_many(
+        &mut self,
+        lw: &LocalWaker,
+        buf: &mut Vec<T>,
+        max: usize,
+    ) -> Poll<usize> {
+        self.0.poll_recv_many(lw, buf, max)
+    }
+
+    /// Creates a future which receives up to `max` queued messages into
+    /// `buf`, resolving to the number of messages received.
+    pub fn recv_many<'a>(&'a mut self, buf: &'a mut Vec<T>, max: usize) -> RecvMany<'a, T> {
+        self.0.recv_many(buf, max)
+    }
+
+    /// Returns the number of messages currently queued in the channel.
+    ///
+    /// Returns `0` once the stream has terminated.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there are no messages queued in the channel.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `None`, as an unbounded channel can hold any number of
+    /// messages.
+    ///
+    /// This mirrors [`Receiver::capacity`](Receiver::capacity), so that both
+    /// kinds of channel can be inspected the same way.
+    pub fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the number of senders connected to the channel.
+    ///
+    /// Returns `0` once the stream has terminated.
+    pub fn sender_count(&self) -> usize {
+        self.0.sender_count()
+    }
 }
 
 impl<T> FusedStream for UnboundedReceiver<T> {
@@ -993,6 +1268,21 @@ impl<T> Inner<T> {
         }
     }
 
+    // Number of messages sent but not yet received. This may briefly count a
+    // message whose sender has not finished pushing it onto the queue.
+    fn len(&self) -> usize {
+        decode_state(self.state.load(SeqCst)).num_messages
+    }
+
+    fn sender_count(&self) -> usize {
+        self.num_senders.load(SeqCst)
+    }
+
+    // Total capacity of a bounded channel: `buffer + num-senders`.
+    fn capacity(&self) -> Option<usize> {
+        self.buffer.map(|buffer| buffer + self.sender_count())
+    }
+
     // Clear `open` flag in the state, keep `num_messages` intact.
     fn set_closed(&self) {
         let curr = self.state.load(SeqCst);
@@ -0,0 +1,404 @@
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll};
+use futures_core::task::__internal::AtomicWaker;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use super::{SendError, SenderTask, TryRecvError, TrySendError};
+
+/// The transmission end of a bounded priority channel.
+///
+/// This value is created by the [`priority_channel`](priority_channel)
+/// function.
+#[derive(Debug)]
+pub struct PrioritySender<P, T> {
+    inner: Option<Arc<Inner<P, T>>>,
+
+    // Handle to the task that is blocked on this sender. This handle is
+    // pushed onto `State::parked_queue` in order to be notified when the
+    // sender becomes unblocked.
+    sender_task: Arc<Mutex<SenderTask>>,
+}
+
+/// The receiving end of a bounded priority channel.
+///
+/// This value is created by the [`priority_channel`](priority_channel)
+/// function.
+#[derive(Debug)]
+pub struct PriorityReceiver<P, T> {
+    inner: Option<Arc<Inner<P, T>>>,
+}
+
+// The channel does not ever project Pin to the inner T
+impl<P, T> Unpin for PrioritySender<P, T> {}
+impl<P, T> Unpin for PriorityReceiver<P, T> {}
+
+#[derive(Debug)]
+struct Inner<P, T> {
+    // Max buffer size of the channel.
+    buffer: usize,
+
+    state: Mutex<State<P, T>>,
+
+    // Handle to the receiver's task.
+    recv_task: AtomicWaker,
+}
+
+#[derive(Debug)]
+struct State<P, T> {
+    // `true` when the channel is open
+    is_open: bool,
+
+    // Pending messages, ordered so that the highest priority is popped first
+    messages: BinaryHeap<Entry<P, T>>,
+
+    // Sequence number given to the next message, used to keep messages of
+    // equal priority in FIFO order
+    next_seq: u64,
+
+    // Senders which exceeded the buffer, waiting for messages to be received
+    parked_queue: VecDeque<Arc<Mutex<SenderTask>>>,
+
+    // Number of senders in existence
+    num_senders: usize,
+}
+
+impl<P, T> State<P, T> {
+    // Clear the `is_open` flag and unpark all senders so that they observe
+    // the channel being closed.
+    fn close(&mut self) {
+        self.is_open = false;
+        while let Some(task) = self.parked_queue.pop_front() {
+            task.lock().unwrap().notify();
+        }
+    }
+}
+
+struct Entry<P, T> {
+    priority: P,
+    seq: u64,
+    msg: T,
+}
+
+impl<P: fmt::Debug, T> fmt::Debug for Entry<P, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("priority", &self.priority)
+            .field("seq", &self.seq)
+            .finish()
+    }
+}
+
+impl<P: Ord, T> PartialEq for Entry<P, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Ord, T> Eq for Entry<P, T> {}
+
+impl<P: Ord, T> PartialOrd for Entry<P, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord, T> Ord for Entry<P, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priorities compare greater so that `BinaryHeap` pops them
+        // first; among equal priorities, earlier messages compare greater.
+        self.priority.cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// Creates a bounded mpsc channel whose receiver yields messages in order of
+/// priority.
+///
+/// Every message is sent along with a priority, and the
+/// [`PriorityReceiver`](PriorityReceiver) always yields the pending message
+/// with the greatest priority. Messages sent with equal priorities are
+/// received in the order they were sent.
+///
+/// Backpressure works exactly as for [`channel`](channel): the channel's
+/// capacity is `buffer + num-senders`, with each sender getting a guaranteed
+/// slot.
+///
+/// The [`PriorityReceiver`](PriorityReceiver) returned implements the
+/// [`Stream`](futures_core::stream::Stream) trait, while
+/// [`PrioritySender`](PrioritySender) implements `Sink` for
+/// `(priority, message)` pairs.
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::mpsc;
+/// use futures::executor::block_on_stream;
+///
+/// let (mut tx, rx) = mpsc::priority_channel(8);
+/// tx.try_send(0, "bulk").unwrap();
+/// tx.try_send(10, "control").unwrap();
+/// tx.try_send(0, "more bulk").unwrap();
+/// drop(tx);
+///
+/// let received: Vec<_> = block_on_stream(rx).collect();
+/// assert_eq!(received, vec!["control", "bulk", "more bulk"]);
+/// ```
+pub fn priority_channel<P: Ord, T>(buffer: usize) -> (PrioritySender<P, T>, PriorityReceiver<P, T>) {
+    let inner = Arc::new(Inner {
+        buffer,
+        state: Mutex::new(State {
+            is_open: true,
+            messages: BinaryHeap::new(),
+            next_seq: 0,
+            parked_queue: VecDeque::new(),
+            num_senders: 1,
+        }),
+        recv_task: AtomicWaker::new(),
+    });
+
+    let tx = PrioritySender {
+        inner: Some(inner.clone()),
+        sender_task: Arc::new(Mutex::new(SenderTask::new())),
+    };
+    let rx = PriorityReceiver {
+        inner: Some(inner),
+    };
+    (tx, rx)
+}
+
+/*
+ *
+ * ===== impl PrioritySender =====
+ *
+ */
+
+impl<P: Ord, T> PrioritySender<P, T> {
+    /// Attempts to send a message with the given priority on this sender,
+    /// returning the message if there was an error.
+    pub fn try_send(&mut self, priority: P, msg: T) -> Result<(), TrySendError<T>> {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return Err(TrySendError::new(SendError::disconnected(), msg)),
+        };
+
+        {
+            let mut state = inner.state.lock().unwrap();
+            if !state.is_open {
+                return Err(TrySendError::new(SendError::disconnected(), msg));
+            }
+
+            // If the sender is currently blocked, reject the message
+            if self.sender_task.lock().unwrap().is_parked {
+                return Err(TrySendError::new(SendError::full(), msg));
+            }
+
+            let seq = state.next_seq;
+            state.next_seq += 1;
+            state.messages.push(Entry { priority, seq, msg });
+
+            // Block if the number of pending messages has exceeded the
+            // configured buffer size. The message is still accepted as it
+            // occupies this sender's guaranteed slot.
+            if state.messages.len() > inner.buffer {
+                let mut sender = self.sender_task.lock().unwrap();
+                sender.task = None;
+                sender.is_parked = true;
+                drop(sender);
+                state.parked_queue.push_back(self.sender_task.clone());
+            }
+        }
+
+        inner.recv_task.wake();
+        Ok(())
+    }
+
+    /// Send a message with the given priority on the channel.
+    ///
+    /// This function should only be called after
+    /// [`poll_ready`](PrioritySender::poll_ready) has reported that the
+    /// channel is ready to receive a message.
+    pub fn start_send(&mut self, priority: P, msg: T) -> Result<(), SendError> {
+        self.try_send(priority, msg)
+            .map_err(|e| e.into_send_error())
+    }
+}
+
+impl<P, T> PrioritySender<P, T> {
+    /// Polls the channel to determine if there is guaranteed capacity to send
+    /// at least one item without waiting.
+    ///
+    /// # Return value
+    ///
+    /// This method returns:
+    ///
+    /// - `Ok(Async::Ready(_))` if there is sufficient capacity;
+    /// - `Ok(Async::Pending)` if the channel may not have
+    ///   capacity, in which case the current task is queued to be notified once
+    ///   capacity is available;
+    /// - `Err(SendError)` if the receiver has been dropped.
+    pub fn poll_ready(
+        &mut self,
+        lw: &LocalWaker
+    ) -> Poll<Result<(), SendError>> {
+        let inner = self.inner.as_ref().ok_or_else(SendError::disconnected)?;
+        let state = inner.state.lock().unwrap();
+        if !state.is_open {
+            return Poll::Ready(Err(SendError::disconnected()));
+        }
+
+        let mut task = self.sender_task.lock().unwrap();
+        if task.is_parked {
+            // Update the task in case the `Sender` has been moved to another
+            // task
+            task.task = Some(lw.clone().into_waker());
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Returns whether this channel is closed without needing a context.
+    pub fn is_closed(&self) -> bool {
+        self.inner.as_ref()
+            .map(|inner| !inner.state.lock().unwrap().is_open)
+            .unwrap_or(true)
+    }
+
+    /// Closes this channel from the sender side, preventing any new messages.
+    pub fn close_channel(&mut self) {
+        if let Some(inner) = &self.inner {
+            inner.state.lock().unwrap().close();
+            inner.recv_task.wake();
+        }
+    }
+
+    /// Disconnects this sender from the channel, closing it if there are no more senders left.
+    pub fn disconnect(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            let mut state = inner.state.lock().unwrap();
+            state.num_senders -= 1;
+            if state.num_senders == 0 {
+                state.close();
+                drop(state);
+                inner.recv_task.wake();
+            }
+        }
+    }
+}
+
+impl<P, T> Clone for PrioritySender<P, T> {
+    fn clone(&self) -> PrioritySender<P, T> {
+        if let Some(inner) = &self.inner {
+            inner.state.lock().unwrap().num_senders += 1;
+        }
+        PrioritySender {
+            inner: self.inner.clone(),
+            sender_task: Arc::new(Mutex::new(SenderTask::new())),
+        }
+    }
+}
+
+impl<P, T> Drop for PrioritySender<P, T> {
+    fn drop(&mut self) {
+        self.disconnect();
+    }
+}
+
+/*
+ *
+ * ===== impl PriorityReceiver =====
+ *
+ */
+
+impl<P: Ord, T> PriorityReceiver<P, T> {
+    /// Closes the receiving half of a channel, without dropping it.
+    ///
+    /// This prevents any further messages from being sent on the channel while
+    /// still enabling the receiver to drain messages that are buffered.
+    pub fn close(&mut self) {
+        if let Some(inner) = &self.inner {
+            inner.state.lock().unwrap().close();
+        }
+    }
+
+    /// Tries to receive the highest priority message without notifying a
+    /// context if empty.
+    ///
+    /// It is not recommended to call this function from inside of a future,
+    /// only when you've otherwise arranged to be notified when the channel is
+    /// no longer empty.
+    pub fn try_next(&mut self) -> Result<Option<T>, TryRecvError> {
+        match self.next_message() {
+            Poll::Ready(msg) => Ok(msg),
+            Poll::Pending => Err(TryRecvError::new()),
+        }
+    }
+
+    fn next_message(&mut self) -> Poll<Option<T>> {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return Poll::Ready(None),
+        };
+
+        let mut state = inner.state.lock().unwrap();
+        match state.messages.pop() {
+            Some(entry) => {
+                // If there are any parked senders, unpark one now that a slot
+                // has been freed.
+                if let Some(task) = state.parked_queue.pop_front() {
+                    task.lock().unwrap().notify();
+                }
+                Poll::Ready(Some(entry.msg))
+            }
+            None if !state.is_open => {
+                drop(state);
+                self.inner = None;
+                Poll::Ready(None)
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<P, T> FusedStream for PriorityReceiver<P, T> {
+    fn is_terminated(&self) -> bool {
+        self.inner.is_none()
+    }
+}
+
+impl<P: Ord, T> Stream for PriorityReceiver<P, T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<Option<T>> {
+        match self.next_message() {
+            Poll::Ready(msg) => Poll::Ready(msg),
+            Poll::Pending => {
+                // There are no messages to read, in this case, park.
+                self.inner.as_ref().unwrap().recv_task.register(lw);
+                // Check the queue again after parking to prevent a race with
+                // a message sent between the first check and `register`.
+                self.next_message()
+            }
+        }
+    }
+}
+
+impl<P, T> Drop for PriorityReceiver<P, T> {
+    fn drop(&mut self) {
+        if let Some(inner) = &self.inner {
+            let messages = {
+                let mut state = inner.state.lock().unwrap();
+                state.close();
+                state.messages.drain().collect::<Vec<_>>()
+            };
+            drop(messages);
+        }
+    }
+}
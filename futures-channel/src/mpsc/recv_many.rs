@@ -0,0 +1,36 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use std::pin::Pin;
+
+use super::Receiver;
+
+/// A future which receives a batch of messages from an mpsc channel.
+///
+/// This is created by the [`Receiver::recv_many`](Receiver::recv_many) and
+/// [`UnboundedReceiver::recv_many`](super::UnboundedReceiver::recv_many)
+/// methods.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct RecvMany<'a, T> {
+    receiver: &'a mut Receiver<T>,
+    buf: &'a mut Vec<T>,
+    max: usize,
+}
+
+// Pinning is never projected to fields
+impl<'a, T> Unpin for RecvMany<'a, T> {}
+
+impl<'a, T> RecvMany<'a, T> {
+    pub(super) fn new(receiver: &'a mut Receiver<T>, buf: &'a mut Vec<T>, max: usize) -> Self {
+        RecvMany { receiver, buf, max }
+    }
+}
+
+impl<'a, T> Future for RecvMany<'a, T> {
+    type Output = usize;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<usize> {
+        let this = &mut *self;
+        this.receiver.poll_recv_many(lw, this.buf, this.max)
+    }
+}
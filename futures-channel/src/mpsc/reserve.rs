@@ -0,0 +1,66 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use std::pin::Pin;
+
+use super::{SendError, Sender, TrySendError};
+
+/// A future which waits for capacity on a bounded mpsc channel.
+///
+/// This is created by the [`Sender::reserve`](Sender::reserve) method.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Reserve<'a, T> {
+    sender: Option<&'a mut Sender<T>>,
+}
+
+// Pinning is never projected to fields
+impl<'a, T> Unpin for Reserve<'a, T> {}
+
+impl<'a, T> Reserve<'a, T> {
+    pub(super) fn new(sender: &'a mut Sender<T>) -> Self {
+        Reserve { sender: Some(sender) }
+    }
+}
+
+impl<'a, T> Future for Reserve<'a, T> {
+    type Output = Result<Permit<'a, T>, SendError>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let res = self.sender.as_mut()
+            .expect("Reserve polled after completion")
+            .poll_ready(lw);
+        match res {
+            Poll::Ready(Ok(())) => {
+                let sender = self.sender.take().unwrap();
+                Poll::Ready(Ok(Permit { sender }))
+            }
+            Poll::Ready(Err(e)) => {
+                self.sender = None;
+                Poll::Ready(Err(e))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A guarantee that the next message sent on a bounded mpsc channel will be
+/// accepted without waiting for capacity.
+///
+/// This is obtained from the [`Reserve`](Reserve) future. The permit holds
+/// on to the sender's guaranteed slot, which only counts towards the
+/// channel's capacity once a message is actually sent, so dropping the
+/// permit without sending releases it without any further effect.
+#[derive(Debug)]
+pub struct Permit<'a, T> {
+    sender: &'a mut Sender<T>,
+}
+
+impl<'a, T> Permit<'a, T> {
+    /// Sends a message using the reserved capacity.
+    ///
+    /// This never fails for lack of capacity. The message is returned in the
+    /// error if the receiver was dropped after the capacity was reserved.
+    pub fn send(self, msg: T) -> Result<(), TrySendError<T>> {
+        self.sender.try_send(msg)
+    }
+}
@@ -2,6 +2,7 @@
 
 use futures_core::future::Future;
 use futures_core::task::{LocalWaker, Poll, Waker};
+use futures_core::task::__internal::AtomicWaker;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
@@ -67,6 +68,14 @@ struct Inner<T> {
     /// Like `rx_task` above, except for the task blocked in
     /// `Sender::poll_cancel`. Additionally, `Lock` cannot be `UnsafeCell`.
     tx_task: Lock<Option<Waker>>,
+
+    /// The task blocked in a `Confirmation`, woken once `rx_done` is set.
+    confirm_task: AtomicWaker,
+
+    /// Set once the `Receiver` has either taken the value out of `data` or
+    /// been dropped. Unlike `complete`, this is never set by the `Sender`,
+    /// which lets a `Confirmation` tell whether the value was delivered.
+    rx_done: AtomicBool,
 }
 
 /// Creates a new one-shot channel for sending values across asynchronous tasks.
@@ -118,6 +127,8 @@ impl<T> Inner<T> {
             data: Lock::new(None),
             rx_task: Lock::new(None),
             tx_task: Lock::new(None),
+            confirm_task: AtomicWaker::new(),
+            rx_done: AtomicBool::new(false),
         }
     }
 
@@ -192,6 +203,31 @@ impl<T> Inner<T> {
         self.complete.load(SeqCst)
     }
 
+    fn poll_confirm(&self, lw: &LocalWaker) -> Poll<Result<(), T>> {
+        // Register before re-checking `rx_done` so that a receiver finishing
+        // in between is guaranteed to wake us.
+        if !self.rx_done.load(SeqCst) {
+            self.confirm_task.register(lw);
+            if !self.rx_done.load(SeqCst) {
+                return Poll::Pending
+            }
+        }
+
+        // If the value is still in the slot then the receiver went away
+        // without taking it, so hand it back.
+        if let Some(mut slot) = self.data.try_lock() {
+            if let Some(t) = slot.take() {
+                return Poll::Ready(Err(t))
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn notify_rx_done(&self) {
+        self.rx_done.store(true, SeqCst);
+        self.confirm_task.wake();
+    }
+
     fn drop_tx(&self) {
         // Flag that we're a completed `Sender` and try to wake up a receiver.
         // Whether or not we actually stored any data will get picked up and
@@ -240,6 +276,8 @@ impl<T> Inner<T> {
         if self.complete.load(SeqCst) {
             if let Some(mut slot) = self.data.try_lock() {
                 if let Some(data) = slot.take() {
+                    drop(slot);
+                    self.notify_rx_done();
                     return Ok(Some(data));
                 }
             }
@@ -281,6 +319,8 @@ impl<T> Inner<T> {
             // will treat the send as a failure.
             if let Some(mut slot) = self.data.try_lock() {
                 if let Some(data) = slot.take() {
+                    drop(slot);
+                    self.notify_rx_done();
                     return Poll::Ready(Ok(data));
                 }
             }
@@ -318,6 +358,10 @@ impl<T> Inner<T> {
                 task.wake()
             }
         }
+
+        // Let a pending `Confirmation` know that the value, if any, will
+        // never be taken.
+        self.notify_rx_done();
     }
 }
 
@@ -336,6 +380,29 @@ impl<T> Sender<T> {
         self.inner.send(t)
     }
 
+    /// Completes this oneshot with a successful result and returns a future
+    /// which resolves once the [`Receiver`](Receiver) has dealt with it.
+    ///
+    /// Unlike [`send`](Sender::send), which only reports whether the value
+    /// could be enqueued, the returned [`Confirmation`](Confirmation)
+    /// resolves to `Ok(())` once the receiver has actually taken the value,
+    /// or to `Err` with the value if the receiver was dropped without taking
+    /// it.
+    pub fn send_and_confirm(self, t: T) -> Confirmation<T> {
+        let inner = self.inner.clone();
+        let failed = self.inner.send(t).err();
+        Confirmation { inner, failed }
+    }
+
+    /// Creates a future which resolves once the associated
+    /// [`Receiver`](Receiver) has been dropped.
+    ///
+    /// This is the future form of [`poll_cancel`](Sender::poll_cancel), which
+    /// makes it usable with combinators such as `select!`.
+    pub fn cancellation(&mut self) -> Cancellation<'_, T> {
+        Cancellation { sender: self }
+    }
+
     /// Polls this `Sender` half to detect whether its associated
     /// [`Receiver`](Receiver) with has been dropped.
     ///
@@ -369,6 +436,61 @@ impl<T> Drop for Sender<T> {
     }
 }
 
+/// A future which resolves when the [`Receiver`](Receiver) of a oneshot has
+/// been dropped.
+///
+/// This is created by the [`Sender::cancellation`](Sender::cancellation)
+/// method.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Cancellation<'a, T> {
+    sender: &'a mut Sender<T>,
+}
+
+// Pinning is never projected to fields
+impl<'a, T> Unpin for Cancellation<'a, T> {}
+
+impl<'a, T> Future for Cancellation<'a, T> {
+    type Output = ();
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<()> {
+        self.sender.poll_cancel(lw)
+    }
+}
+
+/// A future which resolves once a value sent on a oneshot has been taken by
+/// the [`Receiver`](Receiver), or the receiver has been dropped.
+///
+/// This is created by the [`Sender::send_and_confirm`](Sender::send_and_confirm)
+/// method.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Confirmation<T> {
+    inner: Arc<Inner<T>>,
+    // The value if it couldn't be sent in the first place.
+    failed: Option<T>,
+}
+
+// Pinning is never projected to fields
+impl<T> Unpin for Confirmation<T> {}
+
+impl<T> Future for Confirmation<T> {
+    type Output = Result<(), T>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<Result<(), T>> {
+        if let Some(t) = self.failed.take() {
+            return Poll::Ready(Err(t))
+        }
+        self.inner.poll_confirm(lw)
+    }
+}
+
 /// Error returned from a [`Receiver`](Receiver) when the corresponding
 /// [`Sender`](Sender) is dropped.
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -0,0 +1,301 @@
+//! A oneshot channel whose state lives in caller-provided storage.
+//!
+//! Unlike [`oneshot`](crate::oneshot), which allocates its shared state
+//! behind an `Arc`, this channel keeps everything inside a [`OneshotSlot`]
+//! owned by the caller, for example in a `static` or on the stack. The
+//! [`Sender`] and [`Receiver`] halves borrow the slot, so the channel works
+//! without the standard library and without an allocator.
+//!
+//! [`OneshotSlot`]: struct.OneshotSlot.html
+//! [`Sender`]: struct.Sender.html
+//! [`Receiver`]: struct.Receiver.html
+
+use core::cell::UnsafeCell;
+use core::fmt;
+use core::mem;
+use core::pin::Pin;
+use core::sync::atomic::AtomicUsize;
+use core::sync::atomic::Ordering::SeqCst;
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_core::task::__internal::AtomicWaker;
+
+/// Storage for a oneshot channel sending a single `T`.
+///
+/// Call [`split`](OneshotSlot::split) to obtain the sending and receiving
+/// halves.
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::oneshot_slot::OneshotSlot;
+/// use futures::executor::block_on;
+///
+/// static SLOT: OneshotSlot<u32> = OneshotSlot::new();
+///
+/// let (tx, rx) = SLOT.split();
+/// tx.send(3).unwrap();
+/// assert_eq!(block_on(rx), Ok(3));
+/// ```
+pub struct OneshotSlot<T> {
+    // Combination of the flags below describing the channel state.
+    state: AtomicUsize,
+
+    // The value being transferred. Written by the sender before it sets
+    // `VALUE`, and only accessed by the receiver once `VALUE` is set.
+    data: UnsafeCell<Option<T>>,
+
+    // Task blocked in `Receiver::poll`.
+    rx_task: AtomicWaker,
+
+    // Task blocked in `Sender::poll_cancel`.
+    tx_task: AtomicWaker,
+}
+
+/// The halves have been handed out by `split`.
+const SPLIT: usize = 0b0001;
+
+/// `data` holds a value which the receiver has not taken yet.
+const VALUE: usize = 0b0010;
+
+/// The sender has sent a value or been dropped.
+const TX_DONE: usize = 0b0100;
+
+/// The receiver has been dropped.
+const RX_DONE: usize = 0b1000;
+
+// The slot hands out shared references to `data` across the two halves, with
+// the `state` flags making sure only one of them accesses it at a time.
+unsafe impl<T: Send> Send for OneshotSlot<T> {}
+unsafe impl<T: Send> Sync for OneshotSlot<T> {}
+
+/// A means of transmitting a single value through a
+/// [`OneshotSlot`](OneshotSlot).
+///
+/// This is created by the [`OneshotSlot::split`](OneshotSlot::split) method.
+#[derive(Debug)]
+pub struct Sender<'a, T> {
+    slot: &'a OneshotSlot<T>,
+}
+
+/// A future for a value that will be provided through a
+/// [`OneshotSlot`](OneshotSlot).
+///
+/// This is created by the [`OneshotSlot::split`](OneshotSlot::split) method.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Receiver<'a, T> {
+    slot: &'a OneshotSlot<T>,
+}
+
+// The channels do not ever project Pin to the inner T
+impl<'a, T> Unpin for Sender<'a, T> {}
+impl<'a, T> Unpin for Receiver<'a, T> {}
+
+/// Error returned from a [`Receiver`](Receiver) when the corresponding
+/// [`Sender`](Sender) is dropped without sending a value.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Canceled;
+
+impl fmt::Display for Canceled {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "oneshot canceled")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Canceled {
+    fn description(&self) -> &str {
+        "oneshot canceled"
+    }
+}
+
+impl<T> OneshotSlot<T> {
+    /// Creates empty storage for a oneshot channel.
+    ///
+    /// This is a `const fn`, so a slot can be placed in a `static`.
+    pub const fn new() -> OneshotSlot<T> {
+        OneshotSlot {
+            state: AtomicUsize::new(0),
+            data: UnsafeCell::new(None),
+            rx_task: AtomicWaker::new(),
+            tx_task: AtomicWaker::new(),
+        }
+    }
+
+    /// Returns the sending and receiving halves of the channel stored in
+    /// this slot.
+    ///
+    /// # Panics
+    ///
+    /// A slot can only be split once. Panics if this slot has already been
+    /// split and not [`reset`](OneshotSlot::reset) since.
+    pub fn split(&self) -> (Sender<'_, T>, Receiver<'_, T>) {
+        let prev = self.state.fetch_or(SPLIT, SeqCst);
+        assert!(prev & SPLIT == 0, "OneshotSlot::split called more than once");
+        (Sender { slot: self }, Receiver { slot: self })
+    }
+
+    /// Returns this slot to its initial state so that it can be split again,
+    /// dropping any value that was sent but never received.
+    ///
+    /// Having mutable access to the slot guarantees that no halves borrowing
+    /// it are alive.
+    pub fn reset(&mut self) {
+        *self = OneshotSlot::new();
+    }
+
+    fn send(&self, t: T) -> Result<(), T> {
+        if self.state.load(SeqCst) & RX_DONE != 0 {
+            return Err(t)
+        }
+
+        // Only the sender touches `data` until `VALUE` is set.
+        unsafe { *self.data.get() = Some(t) };
+
+        let mut curr = self.state.load(SeqCst);
+        loop {
+            if curr & RX_DONE != 0 {
+                // The receiver went away before it could observe `VALUE`, so
+                // the value is still ours to hand back.
+                let t = unsafe { (*self.data.get()).take() };
+                return Err(t.unwrap())
+            }
+            match self.state.compare_exchange(curr, curr | VALUE | TX_DONE, SeqCst, SeqCst) {
+                Ok(_) => break,
+                Err(actual) => curr = actual,
+            }
+        }
+
+        self.rx_task.wake();
+        Ok(())
+    }
+
+    fn drop_tx(&self) {
+        self.state.fetch_or(TX_DONE, SeqCst);
+        self.rx_task.wake();
+    }
+
+    fn poll_cancel(&self, lw: &LocalWaker) -> Poll<()> {
+        // Register before checking the flag so that a receiver dropped
+        // concurrently is guaranteed to wake us.
+        self.tx_task.register(lw);
+        if self.state.load(SeqCst) & RX_DONE != 0 {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    fn try_recv(&self) -> Result<Option<T>, Canceled> {
+        let state = self.state.load(SeqCst);
+        if state & VALUE != 0 {
+            // Once `VALUE` is set only the receiver touches `data`.
+            let t = unsafe { (*self.data.get()).take() };
+            self.state.fetch_and(!VALUE, SeqCst);
+            Ok(t)
+        } else if state & TX_DONE != 0 {
+            Err(Canceled)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn recv(&self, lw: &LocalWaker) -> Poll<Result<T, Canceled>> {
+        if let Some(t) = self.try_recv()? {
+            return Poll::Ready(Ok(t))
+        }
+        self.rx_task.register(lw);
+        match self.try_recv()? {
+            Some(t) => Poll::Ready(Ok(t)),
+            None => Poll::Pending,
+        }
+    }
+
+    fn drop_rx(&self) {
+        let prev = self.state.fetch_or(RX_DONE, SeqCst);
+        if prev & VALUE != 0 {
+            // The value was sent but never received, drop it now rather than
+            // when the slot itself goes away.
+            drop(unsafe { (*self.data.get()).take() });
+        }
+        self.tx_task.wake();
+    }
+}
+
+impl<T> Default for OneshotSlot<T> {
+    fn default() -> Self {
+        OneshotSlot::new()
+    }
+}
+
+impl<T> fmt::Debug for OneshotSlot<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("OneshotSlot")
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+impl<'a, T> Sender<'a, T> {
+    /// Completes this oneshot with a successful result.
+    ///
+    /// If the value is successfully stored for the [`Receiver`](Receiver) to
+    /// take, then `Ok(())` is returned. If the receiving end was dropped
+    /// before this function was called, however, then `Err` is returned with
+    /// the value provided.
+    pub fn send(self, t: T) -> Result<(), T> {
+        let res = self.slot.send(t);
+        // Sending already marked the sender as done.
+        mem::forget(self);
+        res
+    }
+
+    /// Polls this `Sender` half to detect whether its associated
+    /// [`Receiver`](Receiver) has been dropped.
+    ///
+    /// If `Pending` is returned, the current task is scheduled to be
+    /// notified when the `Receiver` goes away.
+    pub fn poll_cancel(&mut self, lw: &LocalWaker) -> Poll<()> {
+        self.slot.poll_cancel(lw)
+    }
+
+    /// Tests to see whether this `Sender`'s corresponding `Receiver` has been
+    /// dropped.
+    pub fn is_canceled(&self) -> bool {
+        self.slot.state.load(SeqCst) & RX_DONE != 0
+    }
+}
+
+impl<'a, T> Drop for Sender<'a, T> {
+    fn drop(&mut self) {
+        self.slot.drop_tx()
+    }
+}
+
+impl<'a, T> Receiver<'a, T> {
+    /// Attempts to receive the value outside of the context of a task.
+    ///
+    /// Returns `Ok(None)` if no value has been sent yet, and an error if the
+    /// sender was dropped without sending one.
+    pub fn try_recv(&mut self) -> Result<Option<T>, Canceled> {
+        self.slot.try_recv()
+    }
+}
+
+impl<'a, T> Future for Receiver<'a, T> {
+    type Output = Result<T, Canceled>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<Result<T, Canceled>> {
+        self.slot.recv(lw)
+    }
+}
+
+impl<'a, T> Drop for Receiver<'a, T> {
+    fn drop(&mut self) {
+        self.slot.drop_rx()
+    }
+}
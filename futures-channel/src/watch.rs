@@ -0,0 +1,344 @@
+//! A single-producer, multi-consumer channel which only retains the most
+//! recently sent value.
+//!
+//! This is useful for distributing state which changes over time, such as
+//! configuration, to many tasks. Receivers can inspect the current value at
+//! any time with [`Receiver::get_ref`], and used as a [`Stream`] they yield
+//! the latest value each time it changes. Intermediate values sent while a
+//! receiver isn't looking are skipped.
+//!
+//! # Disconnection
+//!
+//! When the [`Sender`] is dropped, receivers yield the latest value if they
+//! have not yet observed it and then terminate. The sender can detect that all
+//! receivers have been dropped with [`Sender::poll_closed`].
+//!
+//! [`Sender`]: struct.Sender.html
+//! [`Sender::poll_closed`]: struct.Sender.html#method.poll_closed
+//! [`Receiver::get_ref`]: struct.Receiver.html#method.get_ref
+//! [`Stream`]: ../../futures_core/stream/trait.Stream.html
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll};
+use futures_core::task::__internal::AtomicWaker;
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::atomic::Ordering::SeqCst;
+
+/// The transmission end of a watch channel.
+///
+/// This value is created by the [`channel`](channel) function.
+#[derive(Debug)]
+pub struct Sender<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The receiving end of a watch channel.
+///
+/// This value is created by the [`channel`](channel) function. Cloning a
+/// receiver creates a new receiver which has observed the same values as the
+/// original.
+#[derive(Debug)]
+pub struct Receiver<T> {
+    inner: Arc<Inner<T>>,
+    // Handle to the task blocked on this receiver, shared with the sender
+    // through `Inner::watchers`.
+    task: Arc<AtomicWaker>,
+    // Version of the last value observed by this receiver.
+    version: usize,
+    terminated: bool,
+}
+
+// The channel does not ever project Pin to the inner T
+impl<T> Unpin for Sender<T> {}
+impl<T> Unpin for Receiver<T> {}
+
+/// A reference to the current value of a watch channel, returned from
+/// [`Receiver::get_ref`](Receiver::get_ref).
+///
+/// The sender is blocked from broadcasting a new value while this reference
+/// is held, so it should be dropped quickly.
+pub struct Ref<'a, T> {
+    inner: RwLockReadGuard<'a, Shared<T>>,
+}
+
+/// The error type returned from [`broadcast`](Sender::broadcast) when all
+/// receivers have been dropped.
+///
+/// The value that failed to send is returned.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("SendError")
+            .finish()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "send failed because all receivers are gone")
+    }
+}
+
+impl<T: Any> Error for SendError<T> {
+    fn description(&self) -> &str {
+        "send failed because all receivers are gone"
+    }
+}
+
+impl<T> SendError<T> {
+    /// Returns the value that was attempted to be sent but failed.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    // The current value, along with a version number which is bumped on each
+    // broadcast. Keeping both under the same lock means receivers never
+    // observe a version without its value.
+    shared: RwLock<Shared<T>>,
+
+    // Set once the sender has been dropped.
+    closed: AtomicBool,
+
+    // Tasks blocked on each of the receivers.
+    watchers: Mutex<Vec<Arc<AtomicWaker>>>,
+
+    num_receivers: AtomicUsize,
+
+    // Handle to the task blocked in `Sender::poll_closed`.
+    cancel_task: AtomicWaker,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    value: T,
+    version: usize,
+}
+
+/// Creates a new watch channel, returning the sender and receiver halves.
+///
+/// All values sent by the [`Sender`](Sender) become visible to each
+/// [`Receiver`](Receiver), and the channel starts out holding `init`.
+///
+/// # Examples
+///
+/// ```
+/// use futures::channel::watch;
+/// use futures::executor::block_on_stream;
+///
+/// let (mut tx, rx) = watch::channel("hello");
+/// assert_eq!(*rx.get_ref(), "hello");
+///
+/// let mut changes = block_on_stream(rx.clone());
+/// tx.broadcast("world").unwrap();
+/// assert_eq!(changes.next(), Some("world"));
+/// assert_eq!(*rx.get_ref(), "world");
+/// ```
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Inner {
+        shared: RwLock::new(Shared {
+            value: init,
+            version: 0,
+        }),
+        closed: AtomicBool::new(false),
+        watchers: Mutex::new(Vec::new()),
+        num_receivers: AtomicUsize::new(0),
+        cancel_task: AtomicWaker::new(),
+    });
+    let rx = Receiver::new(inner.clone(), 0);
+    (Sender { inner }, rx)
+}
+
+/*
+ *
+ * ===== impl Sender =====
+ *
+ */
+
+impl<T> Sender<T> {
+    /// Replaces the value held by the channel and notifies all receivers.
+    ///
+    /// Returns the value in a [`SendError`](SendError) if all receivers have
+    /// been dropped.
+    pub fn broadcast(&mut self, value: T) -> Result<(), SendError<T>> {
+        if self.is_closed() {
+            return Err(SendError(value));
+        }
+
+        {
+            let mut shared = self.inner.shared.write().unwrap();
+            shared.value = value;
+            shared.version = shared.version.wrapping_add(1);
+        }
+
+        self.inner.wake_watchers();
+        Ok(())
+    }
+
+    /// Polls this `Sender` to detect whether all receivers have been dropped.
+    ///
+    /// Returns `Ready` once there are no receivers left, otherwise the current
+    /// task is scheduled to be notified when the last one goes away.
+    pub fn poll_closed(&mut self, lw: &LocalWaker) -> Poll<()> {
+        self.inner.cancel_task.register(lw);
+        if self.is_closed() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Returns whether all receivers have been dropped, without needing a
+    /// context.
+    pub fn is_closed(&self) -> bool {
+        self.inner.num_receivers.load(SeqCst) == 0
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.inner.closed.store(true, SeqCst);
+        self.inner.wake_watchers();
+    }
+}
+
+/*
+ *
+ * ===== impl Receiver =====
+ *
+ */
+
+impl<T> Receiver<T> {
+    fn new(inner: Arc<Inner<T>>, version: usize) -> Receiver<T> {
+        let task = Arc::new(AtomicWaker::new());
+        inner.watchers.lock().unwrap().push(task.clone());
+        inner.num_receivers.fetch_add(1, SeqCst);
+        Receiver {
+            inner,
+            task,
+            version,
+            terminated: false,
+        }
+    }
+
+    /// Returns a reference to the most recently sent value.
+    ///
+    /// This does not mark the value as observed, so the `Stream`
+    /// implementation will still yield it if it is new to this receiver.
+    pub fn get_ref(&self) -> Ref<'_, T> {
+        Ref {
+            inner: self.inner.shared.read().unwrap(),
+        }
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        let mut rx = Receiver::new(self.inner.clone(), self.version);
+        rx.terminated = self.terminated;
+        rx
+    }
+}
+
+impl<T> FusedStream for Receiver<T> {
+    fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<T: Clone> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        lw: &LocalWaker,
+    ) -> Poll<Option<T>> {
+        if self.terminated {
+            return Poll::Ready(None);
+        }
+
+        // Register before checking for a new value so that a broadcast racing
+        // with this poll is guaranteed to wake us.
+        self.task.register(lw);
+
+        // Read `closed` before the value: if the sender was dropped after
+        // broadcasting, its final value is then guaranteed to be seen below.
+        let closed = self.inner.closed.load(SeqCst);
+
+        let value = {
+            let shared = self.inner.shared.read().unwrap();
+            if shared.version != self.version {
+                Some((shared.version, shared.value.clone()))
+            } else {
+                None
+            }
+        };
+
+        match value {
+            Some((version, value)) => {
+                self.version = version;
+                Poll::Ready(Some(value))
+            }
+            None if closed => {
+                self.terminated = true;
+                Poll::Ready(None)
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.inner.watchers.lock().unwrap()
+            .retain(|task| !Arc::ptr_eq(task, &self.task));
+
+        if self.inner.num_receivers.fetch_sub(1, SeqCst) == 1 {
+            self.inner.cancel_task.wake();
+        }
+    }
+}
+
+/*
+ *
+ * ===== impl Inner =====
+ *
+ */
+
+impl<T> Inner<T> {
+    fn wake_watchers(&self) {
+        for task in self.watchers.lock().unwrap().iter() {
+            task.wake();
+        }
+    }
+}
+
+/*
+ *
+ * ===== impl Ref =====
+ *
+ */
+
+impl<'a, T> Deref for Ref<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner.value
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for Ref<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
@@ -0,0 +1,114 @@
+#![feature(futures_api, async_await, await_macro)]
+
+use futures::channel::broadcast::{self, RecvError, TryRecvError};
+use futures::executor::{block_on, block_on_stream};
+use futures::future::poll_fn;
+use futures::stream::{Stream, StreamExt};
+use futures::sink::SinkExt;
+use futures::task::Poll;
+use pin_utils::pin_mut;
+use std::thread;
+
+trait AssertSend: Send {}
+impl AssertSend for broadcast::Sender<i32> {}
+impl AssertSend for broadcast::Receiver<i32> {}
+
+#[test]
+fn every_receiver_sees_every_message() {
+    let (tx, rx1) = broadcast::channel::<i32>(16);
+    let rx2 = tx.subscribe();
+    let rx3 = rx1.clone();
+
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    drop(tx);
+
+    for rx in vec![rx1, rx2, rx3] {
+        let v: Vec<_> = block_on(rx.collect());
+        assert_eq!(v, vec![Ok(1), Ok(2)]);
+    }
+}
+
+#[test]
+fn subscribe_only_sees_later_messages() {
+    let (tx, mut rx1) = broadcast::channel::<i32>(16);
+    tx.send(1).unwrap();
+    let mut rx2 = tx.subscribe();
+    tx.send(2).unwrap();
+
+    assert_eq!(rx1.try_next(), Ok(Some(1)));
+    assert_eq!(rx1.try_next(), Ok(Some(2)));
+    assert_eq!(rx2.try_next(), Ok(Some(2)));
+    assert_eq!(rx2.try_next(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn slow_receiver_lags() {
+    let (tx, mut rx) = broadcast::channel::<i32>(2);
+    for i in 0..5 {
+        tx.send(i).unwrap();
+    }
+
+    assert_eq!(rx.try_next(), Err(TryRecvError::Lagged(3)));
+    assert_eq!(rx.try_next(), Ok(Some(3)));
+    assert_eq!(rx.try_next(), Ok(Some(4)));
+    assert_eq!(rx.try_next(), Err(TryRecvError::Empty));
+
+    tx.send(5).unwrap();
+    tx.send(6).unwrap();
+    tx.send(7).unwrap();
+    drop(tx);
+
+    let v: Vec<_> = block_on_stream(rx).collect();
+    assert_eq!(v, vec![Err(RecvError::Lagged(1)), Ok(6), Ok(7)]);
+}
+
+#[test]
+fn send_fails_without_receivers() {
+    let (tx, rx) = broadcast::channel::<i32>(1);
+    assert_eq!(tx.receiver_count(), 1);
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(1).unwrap_err().into_inner(), 1);
+}
+
+#[test]
+fn receiver_wakes_on_send() {
+    block_on(poll_fn(move |lw| {
+        let (tx, rx) = broadcast::channel::<i32>(1);
+        pin_mut!(rx);
+
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Pending);
+        tx.send(1).unwrap();
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(Some(Ok(1))));
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Pending);
+        drop(tx);
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(None));
+
+        Poll::Ready(())
+    }));
+}
+
+#[test]
+fn sink_and_threads() {
+    let (mut tx, rx) = broadcast::channel::<i32>(16);
+    let receivers: Vec<_> = (0..4).map(|_| tx.subscribe()).collect();
+    drop(rx);
+
+    let handles: Vec<_> = receivers.into_iter().map(|rx| {
+        thread::spawn(move || {
+            block_on(rx.map(Result::unwrap).collect::<Vec<_>>())
+        })
+    }).collect();
+
+    // The inherent `Sender::send` shadows `SinkExt::send`.
+    for i in 0..10 {
+        block_on(SinkExt::send(&mut tx, i)).unwrap();
+    }
+    block_on(tx.close()).unwrap();
+
+    for handle in handles {
+        let v = handle.join().unwrap();
+        assert_eq!(v, (0..10).collect::<Vec<_>>());
+    }
+}
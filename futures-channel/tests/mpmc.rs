@@ -0,0 +1,135 @@
+#![feature(futures_api)]
+
+use futures::channel::mpmc;
+use futures::executor::{block_on, block_on_stream};
+use futures::future::poll_fn;
+use futures::stream::{Stream, StreamExt};
+use futures::sink::{Sink, SinkExt};
+use futures::task::Poll;
+use pin_utils::pin_mut;
+use std::thread;
+use std::time::Duration;
+
+trait AssertSend: Send {}
+impl AssertSend for mpmc::Sender<i32> {}
+impl AssertSend for mpmc::Receiver<i32> {}
+
+#[test]
+fn send_recv() {
+    let (mut tx, rx) = mpmc::channel::<i32>(16);
+
+    block_on(tx.send(1)).unwrap();
+    drop(tx);
+    let v: Vec<_> = block_on(rx.collect());
+    assert_eq!(v, vec![1]);
+}
+
+#[test]
+fn messages_are_distributed() {
+    let (mut tx, mut rx1) = mpmc::channel::<i32>(16);
+    let mut rx2 = rx1.clone();
+
+    for i in 0..4 {
+        tx.try_send(i).unwrap();
+    }
+
+    assert_eq!(rx1.try_next().unwrap(), Some(0));
+    assert_eq!(rx2.try_next().unwrap(), Some(1));
+    assert_eq!(rx2.try_next().unwrap(), Some(2));
+    assert_eq!(rx1.try_next().unwrap(), Some(3));
+    assert!(rx1.try_next().is_err());
+    assert!(rx2.try_next().is_err());
+
+    drop(tx);
+    assert_eq!(rx1.try_next().unwrap(), None);
+    assert_eq!(rx2.try_next().unwrap(), None);
+}
+
+#[test]
+fn send_recv_no_buffer() {
+    block_on(poll_fn(move |lw| {
+        let (tx, rx) = mpmc::channel::<i32>(0);
+        pin_mut!(tx, rx);
+
+        assert!(tx.as_mut().poll_ready(lw).is_ready());
+
+        // Send first message, which takes the sender's guaranteed slot
+        assert!(tx.as_mut().start_send(1).is_ok());
+        assert!(tx.as_mut().poll_ready(lw).is_pending());
+        assert!(tx.as_mut().start_send(0).unwrap_err().is_full());
+
+        // Take the value
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(Some(1)));
+        assert!(tx.as_mut().poll_ready(lw).is_ready());
+
+        Poll::Ready(())
+    }));
+}
+
+#[test]
+fn dropping_receivers_closes_channel() {
+    let (mut tx, rx1) = mpmc::channel::<i32>(1);
+    let rx2 = rx1.clone();
+
+    drop(rx1);
+    assert!(!tx.is_closed());
+    drop(rx2);
+    assert!(tx.is_closed());
+
+    let err = tx.try_send(1).unwrap_err();
+    assert!(err.is_disconnected());
+    assert_eq!(err.into_inner(), 1);
+}
+
+#[test]
+fn close_drains_remaining() {
+    let (mut tx, mut rx1) = mpmc::channel::<i32>(4);
+    let rx2 = rx1.clone();
+
+    tx.try_send(1).unwrap();
+    tx.try_send(2).unwrap();
+    rx1.close();
+    assert!(tx.try_send(3).unwrap_err().is_disconnected());
+
+    let mut rx2 = block_on_stream(rx2);
+    assert_eq!(rx1.try_next().unwrap(), Some(1));
+    assert_eq!(rx2.next(), Some(2));
+    assert_eq!(rx2.next(), None);
+}
+
+#[test]
+fn close_wakes_parked_receivers() {
+    let (_tx, mut rx1) = mpmc::channel::<i32>(4);
+    let mut rx2 = rx1.clone();
+
+    let t = thread::spawn(move || block_on(rx2.next()));
+    // Give the other receiver time to park on the empty queue.
+    thread::sleep(Duration::from_millis(50));
+    rx1.close();
+    assert_eq!(t.join().unwrap(), None);
+}
+
+#[test]
+fn workers_across_threads() {
+    const AMT: u32 = 1000;
+    const WORKERS: usize = 4;
+
+    let (mut tx, rx) = mpmc::channel::<u32>(4);
+
+    let workers: Vec<_> = (0..WORKERS).map(|_| {
+        let rx = rx.clone();
+        thread::spawn(move || block_on(rx.collect::<Vec<_>>()))
+    }).collect();
+    drop(rx);
+
+    for i in 0..AMT {
+        block_on(tx.send(i)).unwrap();
+    }
+    drop(tx);
+
+    let mut all: Vec<_> = workers.into_iter()
+        .flat_map(|w| w.join().unwrap())
+        .collect();
+    all.sort();
+    assert_eq!(all, (0..AMT).collect::<Vec<_>>());
+}
@@ -0,0 +1,76 @@
+#![feature(futures_api)]
+
+use futures::channel::mpsc;
+use futures::executor::{block_on, block_on_stream};
+use futures::future::poll_fn;
+use futures::stream::{Stream, StreamExt};
+use futures::sink::{Sink, SinkExt};
+use futures::task::Poll;
+use pin_utils::pin_mut;
+use std::thread;
+
+trait AssertSend: Send {}
+impl AssertSend for mpsc::PrioritySender<u8, i32> {}
+impl AssertSend for mpsc::PriorityReceiver<u8, i32> {}
+
+#[test]
+fn highest_priority_first() {
+    let (mut tx, rx) = mpsc::priority_channel::<u8, &str>(8);
+    tx.try_send(1, "a").unwrap();
+    tx.try_send(3, "b").unwrap();
+    tx.try_send(2, "c").unwrap();
+    tx.try_send(3, "d").unwrap();
+    drop(tx);
+
+    let v: Vec<_> = block_on(rx.collect());
+    assert_eq!(v, vec!["b", "d", "c", "a"]);
+}
+
+#[test]
+fn backpressure() {
+    block_on(poll_fn(move |lw| {
+        let (tx, rx) = mpsc::priority_channel::<u8, i32>(0);
+        pin_mut!(tx, rx);
+
+        assert!(tx.as_mut().poll_ready(lw).is_ready());
+        assert!(tx.as_mut().start_send((0, 1)).is_ok());
+        assert!(tx.as_mut().poll_ready(lw).is_pending());
+        assert!(tx.as_mut().start_send((9, 2)).unwrap_err().is_full());
+
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(Some(1)));
+        assert!(tx.as_mut().poll_ready(lw).is_ready());
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Pending);
+
+        Poll::Ready(())
+    }));
+}
+
+#[test]
+fn receiver_dropped() {
+    let (mut tx, rx) = mpsc::priority_channel::<u8, i32>(1);
+    drop(rx);
+    assert!(tx.is_closed());
+    let err = tx.try_send(0, 1).unwrap_err();
+    assert!(err.is_disconnected());
+    assert_eq!(err.into_inner(), 1);
+}
+
+#[test]
+fn sink_across_threads() {
+    let (mut tx, rx) = mpsc::priority_channel::<u32, u32>(2);
+    let t = thread::spawn(move || {
+        for i in 0..100 {
+            block_on(tx.send((i % 4, i))).unwrap();
+        }
+    });
+
+    let mut rx = block_on_stream(rx);
+    let mut received = Vec::new();
+    while let Some(msg) = rx.next() {
+        received.push(msg);
+    }
+    received.sort();
+    assert_eq!(received, (0..100).collect::<Vec<_>>());
+
+    t.join().unwrap();
+}
@@ -2,8 +2,8 @@
 
 use futures::channel::{mpsc, oneshot};
 use futures::executor::{block_on, block_on_stream};
-use futures::future::{FutureExt, poll_fn};
-use futures::stream::{Stream, StreamExt};
+use futures::future::{Future, FutureExt, poll_fn};
+use futures::stream::{FusedStream, Stream, StreamExt};
 use futures::sink::{Sink, SinkExt};
 use futures::task::Poll;
 use futures_test::task::noop_local_waker_ref;
@@ -521,3 +521,133 @@ fn try_send_recv() {
     rx.try_next().unwrap();
     rx.try_next().unwrap_err(); // should be empty
 }
+
+#[test]
+fn introspection() {
+    let (mut tx, mut rx) = mpsc::channel::<i32>(2);
+    assert_eq!(tx.capacity(), Some(3));
+    assert_eq!(rx.capacity(), Some(3));
+    assert_eq!(tx.sender_count(), 1);
+    assert!(tx.is_empty());
+
+    let mut tx2 = tx.clone();
+    assert_eq!(rx.sender_count(), 2);
+    assert_eq!(rx.capacity(), Some(4));
+
+    tx.try_send(1).unwrap();
+    tx2.try_send(2).unwrap();
+    assert_eq!(tx.len(), 2);
+    assert_eq!(rx.len(), 2);
+    assert!(!rx.is_empty());
+
+    rx.try_next().unwrap();
+    assert_eq!(tx2.len(), 1);
+
+    drop(tx2);
+    assert_eq!(rx.sender_count(), 1);
+    tx.disconnect();
+    assert_eq!(tx.len(), 0);
+    assert_eq!(tx.sender_count(), 0);
+    assert_eq!(tx.capacity(), None);
+    assert_eq!(rx.sender_count(), 0);
+
+    assert_eq!(rx.try_next().unwrap(), Some(2));
+    assert_eq!(rx.try_next().unwrap(), None);
+    assert_eq!(rx.len(), 0);
+    assert_eq!(rx.capacity(), None);
+}
+
+#[test]
+fn unbounded_introspection() {
+    let (tx, rx) = mpsc::unbounded::<i32>();
+    let tx2 = tx.clone();
+    assert_eq!(rx.sender_count(), 2);
+    assert_eq!(tx.capacity(), None);
+    assert_eq!(rx.capacity(), None);
+
+    tx.unbounded_send(1).unwrap();
+    tx2.unbounded_send(2).unwrap();
+    assert_eq!(tx.len(), 2);
+    assert_eq!(rx.len(), 2);
+    assert!(!tx2.is_empty());
+}
+
+#[test]
+fn recv_many() {
+    let (mut tx, mut rx) = mpsc::channel::<i32>(4);
+    for i in 0..5 {
+        tx.try_send(i).unwrap();
+    }
+    // The sender is parked after exceeding the buffer.
+    assert!(tx.try_send(5).unwrap_err().is_full());
+
+    let mut buf = Vec::new();
+    assert_eq!(block_on(rx.recv_many(&mut buf, 3)), 3);
+    assert_eq!(buf, vec![0, 1, 2]);
+
+    // Receiving unparked the sender.
+    tx.try_send(5).unwrap();
+
+    assert_eq!(block_on(rx.recv_many(&mut buf, 10)), 3);
+    assert_eq!(buf, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(rx.len(), 0);
+
+    drop(tx);
+    assert_eq!(block_on(rx.recv_many(&mut buf, 10)), 0);
+    assert!(rx.is_terminated());
+}
+
+#[test]
+fn poll_recv_many_pending() {
+    let (tx, mut rx) = mpsc::unbounded::<i32>();
+    let mut buf = Vec::new();
+    let lw = noop_local_waker_ref();
+
+    assert_eq!(rx.poll_recv_many(lw, &mut buf, 8), Poll::Pending);
+    tx.unbounded_send(1).unwrap();
+    tx.unbounded_send(2).unwrap();
+    assert_eq!(rx.poll_recv_many(lw, &mut buf, 0), Poll::Ready(0));
+    assert_eq!(rx.poll_recv_many(lw, &mut buf, 8), Poll::Ready(2));
+    assert_eq!(buf, vec![1, 2]);
+}
+
+#[test]
+fn reserve_permit() {
+    block_on(poll_fn(move |lw| {
+        let (mut tx, mut rx) = mpsc::channel::<i32>(0);
+
+        {
+            let reserve = tx.reserve();
+            pin_mut!(reserve);
+            let permit = match reserve.poll(lw) {
+                Poll::Ready(Ok(permit)) => permit,
+                _ => panic!("expected capacity"),
+            };
+            permit.send(1).unwrap();
+        }
+
+        // The guaranteed slot is in use, so reserving has to wait.
+        {
+            let reserve = tx.reserve();
+            pin_mut!(reserve);
+            assert!(reserve.as_mut().poll(lw).is_pending());
+            assert_eq!(rx.poll_next_unpin(lw), Poll::Ready(Some(1)));
+            match reserve.poll(lw) {
+                // Dropping the permit without sending releases it.
+                Poll::Ready(Ok(permit)) => drop(permit),
+                _ => panic!("expected capacity"),
+            }
+        }
+
+        assert!(tx.try_send(2).is_ok());
+        drop(rx);
+        let reserve = tx.reserve();
+        pin_mut!(reserve);
+        match reserve.poll(lw) {
+            Poll::Ready(Err(e)) => assert!(e.is_disconnected()),
+            _ => panic!("expected disconnection"),
+        }
+
+        Poll::Ready(())
+    }));
+}
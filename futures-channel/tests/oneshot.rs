@@ -255,3 +255,56 @@ fn cancel_sends() {
 //         },
 //     }
 // }
+
+#[test]
+fn cancellation_future() {
+    let (mut tx, rx) = oneshot::channel::<u32>();
+    let t = thread::spawn(move || drop(rx));
+    block_on(tx.cancellation());
+    assert!(tx.is_canceled());
+    t.join().unwrap();
+}
+
+#[test]
+fn send_and_confirm_received() {
+    let (tx, rx) = oneshot::channel::<u32>();
+    let confirm = tx.send_and_confirm(1);
+    let t = thread::spawn(move || block_on(rx).unwrap());
+    assert_eq!(block_on(confirm), Ok(()));
+    assert_eq!(t.join().unwrap(), 1);
+}
+
+#[test]
+fn send_and_confirm_pending_until_taken() {
+    let (tx, mut rx) = oneshot::channel::<u32>();
+    let mut confirm = tx.send_and_confirm(1);
+    block_on(poll_fn(|lw| {
+        assert!(confirm.poll_unpin(lw).is_pending());
+        assert_eq!(rx.try_recv(), Ok(Some(1)));
+        assert_eq!(confirm.poll_unpin(lw), Poll::Ready(Ok(())));
+        Poll::Ready(())
+    }));
+}
+
+#[test]
+fn send_and_confirm_receiver_dropped() {
+    let (tx, rx) = oneshot::channel::<u32>();
+    let confirm = tx.send_and_confirm(1);
+    drop(rx);
+    assert_eq!(block_on(confirm), Err(1));
+
+    let (tx, rx) = oneshot::channel::<u32>();
+    drop(rx);
+    assert_eq!(block_on(tx.send_and_confirm(2)), Err(2));
+}
+
+#[test]
+fn send_and_confirm_receiver_dropped_concurrently() {
+    for i in 0..10000 {
+        let (tx, rx) = oneshot::channel::<u32>();
+        let confirm = tx.send_and_confirm(i);
+        let t = thread::spawn(move || drop(rx));
+        assert_eq!(block_on(confirm), Err(i));
+        t.join().unwrap();
+    }
+}
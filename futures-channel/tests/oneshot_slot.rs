@@ -0,0 +1,85 @@
+#![feature(futures_api)]
+
+use futures::channel::oneshot_slot::{Canceled, OneshotSlot};
+use futures::executor::block_on;
+use futures::future::{poll_fn, FutureExt};
+use futures::task::Poll;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+#[test]
+fn send_recv_static() {
+    static SLOT: OneshotSlot<u32> = OneshotSlot::new();
+    let (tx, rx) = SLOT.split();
+    let t = thread::spawn(move || tx.send(1).unwrap());
+    assert_eq!(block_on(rx), Ok(1));
+    t.join().unwrap();
+}
+
+#[test]
+fn sender_dropped() {
+    let slot = OneshotSlot::<u32>::new();
+    let (tx, mut rx) = slot.split();
+    assert_eq!(rx.try_recv(), Ok(None));
+    drop(tx);
+    assert_eq!(rx.try_recv(), Err(Canceled));
+}
+
+#[test]
+fn receiver_dropped() {
+    let slot = OneshotSlot::<u32>::new();
+    let (mut tx, rx) = slot.split();
+    block_on(poll_fn(|lw| {
+        assert!(tx.poll_cancel(lw).is_pending());
+        Poll::Ready(())
+    }));
+    drop(rx);
+    assert!(tx.is_canceled());
+    assert_eq!(tx.send(2), Err(2));
+}
+
+#[test]
+fn pending_until_sent() {
+    let slot = OneshotSlot::<u32>::new();
+    let (tx, mut rx) = slot.split();
+    block_on(poll_fn(|lw| {
+        assert_eq!(rx.poll_unpin(lw), Poll::Pending);
+        Poll::Ready(())
+    }));
+    tx.send(3).unwrap();
+    assert_eq!(block_on(rx), Ok(3));
+}
+
+#[test]
+#[should_panic]
+fn split_twice() {
+    let slot = OneshotSlot::<u32>::new();
+    let _halves = slot.split();
+    let _ = slot.split();
+}
+
+#[test]
+fn reset_drops_unreceived_value() {
+    struct Count(Arc<AtomicUsize>);
+    impl Drop for Count {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let drops = Arc::new(AtomicUsize::new(0));
+    let mut slot = OneshotSlot::new();
+    {
+        let (tx, rx) = slot.split();
+        assert!(tx.send(Count(drops.clone())).is_ok());
+        drop(rx);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    slot.reset();
+    let (tx, rx) = slot.split();
+    assert!(tx.send(Count(drops.clone())).is_ok());
+    assert!(block_on(rx).is_ok());
+    assert_eq!(drops.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,94 @@
+#![feature(futures_api)]
+
+use futures::channel::watch;
+use futures::executor::{block_on, block_on_stream};
+use futures::future::poll_fn;
+use futures::stream::{Stream, StreamExt};
+use futures::task::Poll;
+use pin_utils::pin_mut;
+use std::thread;
+
+trait AssertSend: Send {}
+impl AssertSend for watch::Sender<i32> {}
+impl AssertSend for watch::Receiver<i32> {}
+
+#[test]
+fn get_ref_sees_latest_value() {
+    let (mut tx, rx) = watch::channel(1);
+    assert_eq!(*rx.get_ref(), 1);
+    tx.broadcast(2).unwrap();
+    tx.broadcast(3).unwrap();
+    assert_eq!(*rx.get_ref(), 3);
+}
+
+#[test]
+fn stream_yields_on_change() {
+    block_on(poll_fn(move |lw| {
+        let (mut tx, rx) = watch::channel(0);
+        pin_mut!(rx);
+
+        // The initial value is not a change.
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Pending);
+
+        tx.broadcast(1).unwrap();
+        tx.broadcast(2).unwrap();
+        // Intermediate values are skipped.
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(Some(2)));
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Pending);
+
+        tx.broadcast(3).unwrap();
+        drop(tx);
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(Some(3)));
+        assert_eq!(rx.as_mut().poll_next(lw), Poll::Ready(None));
+
+        Poll::Ready(())
+    }));
+}
+
+#[test]
+fn clone_receiver() {
+    let (mut tx, rx1) = watch::channel("a");
+    tx.broadcast("b").unwrap();
+    let rx2 = rx1.clone();
+    let mut rx1 = block_on_stream(rx1);
+    assert_eq!(rx1.next(), Some("b"));
+
+    tx.broadcast("c").unwrap();
+    drop(tx);
+    assert_eq!(block_on(rx2.collect::<Vec<_>>()), vec!["c"]);
+    assert_eq!(rx1.next(), Some("c"));
+    assert_eq!(rx1.next(), None);
+}
+
+#[test]
+fn sender_detects_closed() {
+    block_on(poll_fn(move |lw| {
+        let (mut tx, rx1) = watch::channel(0);
+        let rx2 = rx1.clone();
+
+        assert_eq!(tx.poll_closed(lw), Poll::Pending);
+        drop(rx1);
+        assert!(!tx.is_closed());
+        drop(rx2);
+        assert!(tx.is_closed());
+        assert_eq!(tx.poll_closed(lw), Poll::Ready(()));
+        assert_eq!(tx.broadcast(1).unwrap_err().into_inner(), 1);
+
+        Poll::Ready(())
+    }));
+}
+
+#[test]
+fn receive_across_threads() {
+    let (mut tx, rx) = watch::channel(0);
+    let handle = thread::spawn(move || {
+        block_on(rx.filter(|v| futures::future::ready(*v == 10)).next())
+    });
+
+    for i in 1..=10 {
+        tx.broadcast(i).unwrap();
+    }
+    drop(tx);
+
+    assert_eq!(handle.join().unwrap(), Some(10));
+}
@@ -133,13 +133,15 @@ const REGISTERING: usize = 0b01;
 /// The waker currently registered with the `AtomicWaker` cell is being woken.
 const WAKING: usize = 0b10;
 
+// Make sure that task is Sync
+trait AssertSync: Sync {}
+impl AssertSync for Waker {}
+
 impl AtomicWaker {
     /// Create an `AtomicWaker`.
-    pub fn new() -> AtomicWaker {
-        // Make sure that task is Sync
-        trait AssertSync: Sync {}
-        impl AssertSync for Waker {}
-
+    ///
+    /// This is a `const fn`, so an `AtomicWaker` can be placed in a `static`.
+    pub const fn new() -> AtomicWaker {
         AtomicWaker {
             state: AtomicUsize::new(WAITING),
             waker: UnsafeCell::new(None),
@@ -0,0 +1,94 @@
+#![feature(test, futures_api)]
+
+extern crate test;
+use crate::test::Bencher;
+
+use futures::executor::{block_on, ThreadPool};
+use futures::future::{self, Future};
+use futures::task::{LocalWaker, Poll, SpawnExt};
+use std::pin::Pin;
+use std::sync::mpsc;
+
+#[bench]
+fn spawn_many(b: &mut Bencher) {
+    const NUM: usize = 10_000;
+
+    let mut pool = ThreadPool::new().unwrap();
+
+    b.iter(|| {
+        let (tx, rx) = mpsc::sync_channel(NUM);
+        for _ in 0..NUM {
+            let tx = tx.clone();
+            pool.spawn(future::lazy(move |_| {
+                tx.send(()).unwrap();
+            })).unwrap();
+        }
+        for _ in 0..NUM {
+            rx.recv().unwrap();
+        }
+    });
+}
+
+#[bench]
+fn yield_many(b: &mut Bencher) {
+    const TASKS: usize = 200;
+    const YIELDS: usize = 500;
+
+    struct Yield {
+        rem: usize,
+    }
+
+    impl Future for Yield {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+            if self.rem == 0 {
+                Poll::Ready(())
+            } else {
+                self.rem -= 1;
+                lw.wake();
+                Poll::Pending
+            }
+        }
+    }
+
+    let mut pool = ThreadPool::new().unwrap();
+
+    b.iter(|| {
+        let handles = (0..TASKS)
+            .map(|_| pool.spawn_with_handle(Yield { rem: YIELDS }).unwrap())
+            .collect::<Vec<_>>();
+        block_on(future::join_all(handles));
+    });
+}
+
+#[bench]
+fn chained_spawn(b: &mut Bencher) {
+    const CHAINS: usize = 100;
+    const DEPTH: usize = 100;
+
+    // Each task spawns the next one in its chain from a worker thread, which
+    // exercises the per-worker queues and stealing.
+    fn spawn_chain(mut pool: ThreadPool, rem: usize, done: mpsc::SyncSender<()>) {
+        if rem == 0 {
+            done.send(()).unwrap();
+            return;
+        }
+        let next = pool.clone();
+        pool.spawn(future::lazy(move |_| {
+            spawn_chain(next, rem - 1, done);
+        })).unwrap();
+    }
+
+    let pool = ThreadPool::new().unwrap();
+
+    b.iter(|| {
+        let (tx, rx) = mpsc::sync_channel(CHAINS);
+        for _ in 0..CHAINS {
+            spawn_chain(pool.clone(), DEPTH, tx.clone());
+        }
+        for _ in 0..CHAINS {
+            rx.recv().unwrap();
+        }
+    });
+}
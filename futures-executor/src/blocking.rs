@@ -0,0 +1,154 @@
+use futures_core::future::FutureObj;
+use futures_util::future::FutureExt;
+use futures_util::task::noop_local_waker_ref;
+use std::collections::VecDeque;
+use std::mem;
+use std::prelude::v1::*;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// An elastic pool of threads running the blocking jobs of a `ThreadPool`.
+///
+/// Threads are spawned on demand, up to a maximum, and exit once they have
+/// been idle for the keep-alive duration. Jobs submitted while all threads
+/// are busy and the maximum has been reached wait in a queue.
+pub(crate) struct BlockingPool {
+    state: Arc<State>,
+}
+
+pub(crate) struct Config {
+    pub(crate) max_threads: usize,
+    pub(crate) keep_alive: Duration,
+    pub(crate) stack_size: usize,
+    pub(crate) name_prefix: Option<String>,
+}
+
+struct State {
+    inner: Mutex<Inner>,
+    cv: Condvar,
+    config: Config,
+}
+
+struct Inner {
+    queue: VecDeque<Job>,
+    // Number of threads which have not exited yet.
+    threads: usize,
+    // Number of threads waiting on `cv` for a job.
+    idle: usize,
+    // Number of idle threads which have been notified of a new job but have
+    // not woken up yet.
+    notified: usize,
+    // Number of threads spawned so far, used to name them.
+    spawned: usize,
+    // Set once the threads should exit after draining the queue.
+    closed: bool,
+}
+
+// A blocking job is a future which completes the first time it is polled.
+type Job = FutureObj<'static, ()>;
+
+impl BlockingPool {
+    pub(crate) fn new(config: Config) -> BlockingPool {
+        BlockingPool {
+            state: Arc::new(State {
+                inner: Mutex::new(Inner {
+                    queue: VecDeque::new(),
+                    threads: 0,
+                    idle: 0,
+                    notified: 0,
+                    spawned: 0,
+                    closed: false,
+                }),
+                cv: Condvar::new(),
+                config,
+            }),
+        }
+    }
+
+    // Queue a job, handing it to an idle thread or spawning a new one if
+    // possible.
+    pub(crate) fn spawn(&self, job: Job) {
+        let mut inner = self.state.inner.lock().unwrap();
+        inner.queue.push_back(job);
+
+        if inner.idle > inner.notified {
+            inner.notified += 1;
+            self.state.cv.notify_one();
+            return
+        }
+        if inner.threads == self.state.config.max_threads {
+            return
+        }
+
+        inner.threads += 1;
+        inner.spawned += 1;
+        let mut thread_builder = thread::Builder::new();
+        if let Some(ref name_prefix) = self.state.config.name_prefix {
+            thread_builder = thread_builder.name(
+                format!("{}blocking-{}", name_prefix, inner.spawned));
+        }
+        if self.state.config.stack_size > 0 {
+            thread_builder = thread_builder.stack_size(self.state.config.stack_size);
+        }
+        let state = self.state.clone();
+        if thread_builder.spawn(move || state.run()).is_err() {
+            inner.threads -= 1;
+            // With no thread left to pick it up, the job would never run.
+            // Dropping it resolves its handle as cancelled, which must
+            // happen outside of the lock.
+            if inner.threads == 0 {
+                let jobs = mem::replace(&mut inner.queue, VecDeque::new());
+                drop(inner);
+                drop(jobs);
+            }
+        }
+    }
+
+    // Drop the jobs which have not started running, resolving their handles
+    // as cancelled.
+    pub(crate) fn abandon(&self) {
+        let jobs = mem::replace(&mut self.state.inner.lock().unwrap().queue, VecDeque::new());
+        drop(jobs);
+    }
+
+    // Tell the threads to exit once the queue is empty.
+    pub(crate) fn close(&self) {
+        let mut inner = self.state.inner.lock().unwrap();
+        if !inner.closed {
+            inner.closed = true;
+            self.state.cv.notify_all();
+        }
+    }
+}
+
+impl State {
+    fn run(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        loop {
+            if let Some(mut job) = inner.queue.pop_front() {
+                drop(inner);
+                // Panics are caught by the job itself, and reported through
+                // its handle.
+                let _ = job.poll_unpin(noop_local_waker_ref());
+                drop(job);
+                inner = self.inner.lock().unwrap();
+                continue
+            }
+            if inner.closed {
+                break
+            }
+
+            inner.idle += 1;
+            let (guard, res) = self.cv.wait_timeout(inner, self.config.keep_alive).unwrap();
+            inner = guard;
+            inner.idle -= 1;
+            if inner.notified > 0 {
+                inner.notified -= 1;
+            } else if res.timed_out() && inner.queue.is_empty() {
+                break
+            }
+        }
+        inner.threads -= 1;
+    }
+}
@@ -0,0 +1,207 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll, Waker};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::prelude::v1::*;
+use std::sync::{Arc, Mutex};
+
+/// A handle to a task spawned with
+/// [`ThreadPool::spawn_handle`](crate::ThreadPool::spawn_handle) or
+/// [`LocalSpawner::spawn_local_handle`](crate::LocalSpawner::spawn_local_handle).
+///
+/// The handle is a future which resolves to the output of the task once it
+/// has completed. Dropping the handle detaches the task, which keeps running
+/// in the background; use [`abort`](JoinHandle::abort) to stop it instead.
+pub struct JoinHandle<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The error returned from a [`JoinHandle`](JoinHandle) when the task did not
+/// run to completion.
+#[derive(Debug)]
+pub enum JoinError {
+    /// The task was aborted, or its executor was dropped before it
+    /// completed.
+    Cancelled,
+    /// The task panicked. This contains the panic payload.
+    Panicked(Box<dyn Any + Send + 'static>),
+}
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinError::Cancelled => write!(fmt, "task was cancelled"),
+            JoinError::Panicked(_) => write!(fmt, "task panicked"),
+        }
+    }
+}
+
+impl Error for JoinError {
+    fn description(&self) -> &str {
+        match self {
+            JoinError::Cancelled => "task was cancelled",
+            JoinError::Panicked(_) => "task panicked",
+        }
+    }
+}
+
+/// The spawned side of a `JoinHandle`, which polls the future and stores its
+/// result for the handle.
+#[must_use = "futures do nothing unless polled"]
+pub(crate) struct JoinTask<Fut: Future> {
+    future: Fut,
+    inner: Arc<Inner<Fut::Output>>,
+    done: bool,
+}
+
+struct Inner<T> {
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    // The output of the task, until it is taken by the handle.
+    result: Option<Result<T, JoinError>>,
+    // Set once `result` has been filled in.
+    complete: bool,
+    // Set by `JoinHandle::abort`.
+    aborted: bool,
+    // The task waiting on the handle.
+    join_waker: Option<Waker>,
+    // The spawned task, to wake it up when aborted.
+    task_waker: Option<Waker>,
+}
+
+pub(crate) fn join_handle<Fut: Future>(future: Fut) -> (JoinTask<Fut>, JoinHandle<Fut::Output>) {
+    let inner = Arc::new(Inner {
+        state: Mutex::new(State {
+            result: None,
+            complete: false,
+            aborted: false,
+            join_waker: None,
+            task_waker: None,
+        }),
+    });
+    let task = JoinTask {
+        future,
+        inner: inner.clone(),
+        done: false,
+    };
+    (task, JoinHandle { inner })
+}
+
+impl<T> Inner<T> {
+    fn complete(&self, result: Result<T, JoinError>) {
+        let waker = {
+            let mut state = self.state.lock().unwrap();
+            state.result = Some(result);
+            state.complete = true;
+            state.task_waker = None;
+            state.join_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> JoinHandle<T> {
+    /// Aborts the task associated with this handle.
+    ///
+    /// The task is dropped the next time the executor gets to it, without
+    /// being polled again, and the handle then resolves to
+    /// `Err(JoinError::Cancelled)`. This has no effect if the task has
+    /// already completed.
+    pub fn abort(&self) {
+        let waker = {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.complete {
+                return
+            }
+            state.aborted = true;
+            state.task_waker.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let mut state = self.inner.state.lock().unwrap();
+        if let Some(result) = state.result.take() {
+            return Poll::Ready(result)
+        }
+        assert!(!state.complete, "JoinHandle polled after completion");
+        match &state.join_waker {
+            Some(waker) if lw.will_wake_nonlocal(waker) => {}
+            _ => state.join_waker = Some(lw.clone().into_waker()),
+        }
+        Poll::Pending
+    }
+}
+
+impl<Fut: Future> JoinTask<Fut> {
+    unsafe_pinned!(future: Fut);
+    unsafe_unpinned!(done: bool);
+}
+
+impl<Fut: Future> Future for JoinTask<Fut> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            if state.aborted {
+                drop(state);
+                *self.as_mut().done() = true;
+                self.inner.complete(Err(JoinError::Cancelled));
+                return Poll::Ready(())
+            }
+            match &state.task_waker {
+                Some(waker) if lw.will_wake_nonlocal(waker) => {}
+                _ => state.task_waker = Some(lw.clone().into_waker()),
+            }
+        }
+
+        let result = match panic::catch_unwind(AssertUnwindSafe(|| self.as_mut().future().poll(lw))) {
+            Ok(Poll::Pending) => return Poll::Pending,
+            Ok(Poll::Ready(output)) => Ok(output),
+            Err(e) => Err(JoinError::Panicked(e)),
+        };
+        *self.as_mut().done() = true;
+        self.inner.complete(result);
+        Poll::Ready(())
+    }
+}
+
+impl<Fut: Future> Drop for JoinTask<Fut> {
+    fn drop(&mut self) {
+        // The executor went away without running the task to completion.
+        if !self.done {
+            self.inner.complete(Err(JoinError::Cancelled));
+        }
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle")
+            .field("complete", &self.inner.state.lock().unwrap().complete)
+            .finish()
+    }
+}
+
+impl<Fut: Future> fmt::Debug for JoinTask<Fut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinTask")
+            .field("done", &self.done)
+            .finish()
+    }
+}
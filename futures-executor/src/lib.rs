@@ -13,12 +13,27 @@ mod local_pool;
 #[cfg(feature = "std")]
 pub use crate::local_pool::{block_on, block_on_stream, BlockingStream, LocalPool, LocalSpawner};
 
+#[cfg(feature = "std")]
+mod join_handle;
+#[cfg(feature = "std")]
+pub use crate::join_handle::{JoinError, JoinHandle};
+
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "std")]
+pub use crate::metrics::Metrics;
+
+#[cfg(feature = "std")]
+mod blocking;
 #[cfg(feature = "std")]
 mod unpark_mutex;
 #[cfg(feature = "std")]
 mod thread_pool;
 #[cfg(feature = "std")]
-pub use crate::thread_pool::{ThreadPool, ThreadPoolBuilder};
+pub use crate::thread_pool::{Shutdown, ThreadPool, ThreadPoolBuilder};
+
+#[cfg(feature = "std")]
+pub mod timer;
 
 #[cfg(feature = "std")]
 mod enter;
@@ -1,20 +1,28 @@
 use crate::{enter, ThreadPool};
+use crate::join_handle::{join_handle, JoinHandle};
+use crate::metrics::{Counters, Metrics};
 use futures_core::future::{Future, FutureObj, LocalFutureObj};
 use futures_core::stream::{Stream};
 use futures_core::task::{
-    self, Poll, LocalWaker, Wake,
+    self, Poll, LocalWaker, Wake, Waker,
     Spawn, LocalSpawn, SpawnError,
 };
+use futures_util::future::FutureExt;
 use futures_util::stream::FuturesUnordered;
 use futures_util::stream::StreamExt;
+use futures_util::task::local_waker_ref_from_nonlocal;
 use lazy_static::lazy_static;
 use pin_utils::pin_mut;
-use std::cell::{RefCell};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
 use std::prelude::v1::*;
 use std::rc::{Rc, Weak};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread::{self, Thread};
+use std::time::Instant;
 
 /// A single-threaded task pool for polling futures to completion.
 ///
@@ -27,10 +35,19 @@ use std::thread::{self, Thread};
 /// [`spawner()`](LocalPool::spawner) method. Because the executor is
 /// single-threaded, it supports a special form of task spawning for non-`Send`
 /// futures, via [`spawn_local_obj`](LocalSpawner::spawn_local_obj).
+///
+/// The pool polls its tasks in turns. Within a turn each ready task is polled
+/// at most once, so a task which keeps waking itself, for example with
+/// [`yield_now`](futures_util::future::yield_now), cannot starve the others.
+/// The number of polls in a single turn is further bounded by the
+/// [poll budget](LocalPool::set_poll_budget). Between turns, `run_until`
+/// polls the future it is driving.
 #[derive(Debug)]
 pub struct LocalPool {
-    pool: FuturesUnordered<LocalFutureObj<'static, ()>>,
+    pool: FuturesUnordered<LocalTask>,
     incoming: Rc<Incoming>,
+    counters: Arc<Counters>,
+    budget: Rc<Budget>,
 }
 
 /// A handle to a [`LocalPool`](LocalPool) that implements
@@ -42,6 +59,37 @@ pub struct LocalSpawner {
 
 type Incoming = RefCell<Vec<LocalFutureObj<'static, ()>>>;
 
+// A task in a `LocalPool`, which updates the pool's metrics as it is polled
+// and is only polled when the pool's budget allows it.
+struct LocalTask {
+    future: LocalFutureObj<'static, ()>,
+    waker: Arc<TaskWaker>,
+    budget: Rc<Budget>,
+    // The last turn of the pool in which this task was polled.
+    last_turn: usize,
+}
+
+// Scheduling state of a `LocalPool` shared with its tasks.
+struct Budget {
+    // Incremented each time the pool starts a turn of polling its tasks.
+    turn: Cell<usize>,
+    // Number of tasks polled in the current turn.
+    polls: Cell<usize>,
+    // Maximum number of tasks polled in a single turn.
+    limit: Cell<usize>,
+    // Tasks which were ready during the current turn but will only be polled
+    // in the next one.
+    deferred: RefCell<Vec<LocalWaker>>,
+}
+
+const DEFAULT_POLL_BUDGET: usize = 128;
+
+// Wraps the waker handed to a `LocalTask` in order to count its wakeups.
+struct TaskWaker {
+    counters: Arc<Counters>,
+    waker: Mutex<Option<Waker>>,
+}
+
 pub(crate) struct ThreadNotify {
     thread: Thread
 }
@@ -83,9 +131,42 @@ impl LocalPool {
         LocalPool {
             pool: FuturesUnordered::new(),
             incoming: Default::default(),
+            counters: Arc::new(Counters::new(1)),
+            budget: Rc::new(Budget {
+                // Tasks start out with `last_turn == 0`, so turns start at 1.
+                turn: Cell::new(0),
+                polls: Cell::new(0),
+                limit: Cell::new(DEFAULT_POLL_BUDGET),
+                deferred: RefCell::new(Vec::new()),
+            }),
         }
     }
 
+    /// Sets the maximum number of tasks polled in a single turn of the pool.
+    ///
+    /// Once a turn has used up its budget, the remaining ready tasks are
+    /// polled in the next turn, after `run_until` has had a chance to poll
+    /// the future it is driving. Lower budgets make the pool more responsive
+    /// to that future at the cost of more overhead. The default is 128.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `budget == 0`.
+    pub fn set_poll_budget(&mut self, budget: usize) {
+        assert!(budget > 0, "poll budget must be positive");
+        self.budget.limit.set(budget);
+    }
+
+    /// Returns a snapshot of the counters kept by this pool.
+    ///
+    /// Tasks which have been spawned but not picked up by the pool yet are
+    /// included in the count of spawned tasks.
+    pub fn metrics(&self) -> Metrics {
+        let mut metrics = self.counters.snapshot();
+        metrics.spawned += self.incoming.borrow().len();
+        metrics
+    }
+
     /// Get a clonable handle to the pool as a [`Spawn`].
     pub fn spawner(&self) -> LocalSpawner {
         LocalSpawner {
@@ -157,16 +238,113 @@ impl LocalPool {
         })
     }
 
-    // Make maximal progress on the entire pool of spawned task, returning `Ready`
-    // if the pool is empty and `Pending` if no further progress can be made.
+    /// Runs all tasks in the pool and returns once no more progress can be
+    /// made on any of them.
+    ///
+    /// Unlike [`run`](LocalPool::run), this function never blocks the thread
+    /// waiting for a task to be woken. Tasks which are ready are polled, and
+    /// so are any tasks they wake or spawn, until none of the tasks in the
+    /// pool are ready. This is useful for driving a pool step by step in
+    /// tests.
+    ///
+    /// Note that this function does not return if a task keeps waking itself.
+    ///
+    /// ```
+    /// #![feature(futures_api)]
+    /// use futures::executor::LocalPool;
+    /// use futures::future::{empty, ready};
+    /// use futures::task::SpawnExt;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let mut spawner = pool.spawner();
+    ///
+    /// spawner.spawn(ready(())).unwrap();
+    /// spawner.spawn(empty()).unwrap();
+    ///
+    /// // Run the two tasks, completing the first one.
+    /// pool.run_until_stalled();
+    /// ```
+    pub fn run_until_stalled(&mut self) {
+        run_executor(|local_waker| {
+            loop {
+                if let Poll::Ready(()) = self.poll_pool(local_waker) {
+                    break;
+                }
+                // A turn which didn't poll anything means that none of the
+                // tasks are ready.
+                if self.budget.polls.get() == 0 {
+                    break;
+                }
+            }
+            Poll::Ready(())
+        })
+    }
+
+    /// Polls at most one ready task in the pool, without blocking the thread.
+    ///
+    /// Returns `true` if a task was polled, and `false` if no task was ready.
+    /// Newly spawned tasks are ready until they are polled for the first time.
+    ///
+    /// ```
+    /// #![feature(futures_api)]
+    /// use futures::executor::LocalPool;
+    /// use futures::future::ready;
+    /// use futures::task::SpawnExt;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let mut spawner = pool.spawner();
+    ///
+    /// spawner.spawn(ready(())).unwrap();
+    /// spawner.spawn(ready(())).unwrap();
+    ///
+    /// assert!(pool.try_run_one());
+    /// assert!(pool.try_run_one());
+    /// assert!(!pool.try_run_one());
+    /// ```
+    pub fn try_run_one(&mut self) -> bool {
+        run_executor(|local_waker| {
+            let limit = self.budget.limit.replace(1);
+            let _ = self.poll_pool(local_waker);
+            self.budget.limit.set(limit);
+            Poll::Ready(self.budget.polls.get() > 0)
+        })
+    }
+
+    // Run a turn of the pool, polling each ready task at most once and at most
+    // as many tasks as the budget allows. Returns `Ready` if the pool is empty
+    // and `Pending` otherwise; tasks which did not get to run this turn will
+    // have woken `local_waker`.
     fn poll_pool(&mut self, local_waker: &LocalWaker) -> Poll<()> {
-        // state for the FuturesUnordered, which will never be used
+        self.budget.turn.set(self.budget.turn.get().wrapping_add(1));
+        self.budget.polls.set(0);
+
+        let res = self.poll_turn(local_waker);
+
+        // Requeue the tasks which were deferred to the next turn. This wakes
+        // the pool through the `FuturesUnordered`.
+        let deferred = mem::replace(&mut *self.budget.deferred.borrow_mut(), Vec::new());
+        for waker in deferred {
+            waker.wake();
+        }
+        res
+    }
+
+    fn poll_turn(&mut self, local_waker: &LocalWaker) -> Poll<()> {
         loop {
             // empty the incoming queue of newly-spawned tasks
             {
                 let mut incoming = self.incoming.borrow_mut();
-                for task in incoming.drain(..) {
-                    self.pool.push(task)
+                for future in incoming.drain(..) {
+                    self.counters.spawned(1);
+                    self.pool.push(LocalTask {
+                        future,
+                        waker: Arc::new(TaskWaker {
+                            counters: self.counters.clone(),
+                            waker: Mutex::new(None),
+                        }),
+                        budget: self.budget.clone(),
+                        last_turn: 0,
+                    })
                 }
             }
 
@@ -186,6 +364,68 @@ impl LocalPool {
     }
 }
 
+impl Future for LocalTask {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        let this = &mut *self;
+
+        // Defer the task to the next turn if it has already been polled in
+        // this one, or the turn has used up its budget.
+        let turn = this.budget.turn.get();
+        if this.last_turn == turn || this.budget.polls.get() >= this.budget.limit.get() {
+            this.budget.deferred.borrow_mut().push(lw.clone());
+            return Poll::Pending;
+        }
+        this.last_turn = turn;
+        this.budget.polls.set(this.budget.polls.get() + 1);
+
+        {
+            let mut waker = this.waker.waker.lock().unwrap();
+            match &*waker {
+                Some(w) if lw.will_wake_nonlocal(w) => {}
+                _ => *waker = Some(lw.clone().into_waker()),
+            }
+        }
+
+        let local_waker = local_waker_ref_from_nonlocal(&this.waker);
+        let start = Instant::now();
+        let res = this.future.poll_unpin(&local_waker);
+        this.waker.counters.polled(0, start);
+        if res.is_ready() {
+            this.waker.counters.completed();
+        }
+        res
+    }
+}
+
+impl fmt::Debug for LocalTask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalTask")
+            .field("contents", &"...")
+            .finish()
+    }
+}
+
+impl fmt::Debug for Budget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Budget")
+            .field("turn", &self.turn.get())
+            .field("polls", &self.polls.get())
+            .field("limit", &self.limit.get())
+            .finish()
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(arc_self: &Arc<Self>) {
+        arc_self.counters.woken();
+        if let Some(waker) = &*arc_self.waker.lock().unwrap() {
+            waker.wake();
+        }
+    }
+}
+
 impl Default for LocalPool {
     fn default() -> Self {
         Self::new()
@@ -251,6 +491,38 @@ impl<S: Stream + Unpin> Iterator for BlockingStream<S> {
     }
 }
 
+impl LocalSpawner {
+    /// Spawns a task that polls the given future to completion, returning a
+    /// [`JoinHandle`](crate::JoinHandle) to its output.
+    ///
+    /// The future does not need to be `Send`. Dropping the handle detaches
+    /// the task, which can be stopped explicitly with
+    /// [`abort`](crate::JoinHandle::abort). If the task panics, the panic is
+    /// caught and reported through the handle.
+    ///
+    /// ```
+    /// #![feature(futures_api)]
+    /// use futures::executor::LocalPool;
+    /// use futures::future::lazy;
+    /// use std::rc::Rc;
+    ///
+    /// let mut pool = LocalPool::new();
+    /// let data = Rc::new(3);
+    /// let handle = pool.spawner()
+    ///     .spawn_local_handle(lazy(move |_| *data * 2))
+    ///     .unwrap();
+    /// assert_eq!(pool.run_until(handle).unwrap(), 6);
+    /// ```
+    pub fn spawn_local_handle<Fut>(&mut self, future: Fut) -> Result<JoinHandle<Fut::Output>, SpawnError>
+        where Fut: Future + 'static,
+              Fut::Output: 'static,
+    {
+        let (task, handle) = join_handle(future);
+        self.spawn_local_obj(LocalFutureObj::new(Box::new(task)))?;
+        Ok(handle)
+    }
+}
+
 impl Spawn for LocalSpawner {
     fn spawn_obj(
         &mut self,
@@ -0,0 +1,107 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// A snapshot of the counters kept by an executor, returned from
+/// [`ThreadPool::metrics`](crate::ThreadPool::metrics) and
+/// [`LocalPool::metrics`](crate::LocalPool::metrics).
+///
+/// All counters start at zero when the executor is created and only ever
+/// increase, with the exception of [`alive`](Metrics::alive).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub(crate) spawned: usize,
+    pub(crate) completed: usize,
+    pub(crate) polls: usize,
+    pub(crate) poll_time: Duration,
+    pub(crate) wakeups: usize,
+}
+
+impl Metrics {
+    /// The number of tasks spawned onto the executor.
+    pub fn spawned(&self) -> usize {
+        self.spawned
+    }
+
+    /// The number of tasks which have run to completion.
+    pub fn completed(&self) -> usize {
+        self.completed
+    }
+
+    /// The number of tasks which have been spawned but have not completed
+    /// yet.
+    pub fn alive(&self) -> usize {
+        self.spawned.saturating_sub(self.completed)
+    }
+
+    /// The number of times a task has been polled.
+    pub fn polls(&self) -> usize {
+        self.polls
+    }
+
+    /// The total time spent polling tasks.
+    pub fn poll_time(&self) -> Duration {
+        self.poll_time
+    }
+
+    /// The number of times a task has been woken.
+    pub fn wakeups(&self) -> usize {
+        self.wakeups
+    }
+}
+
+/// The live counters behind `Metrics`, shared between an executor and its
+/// tasks.
+#[derive(Debug)]
+pub(crate) struct Counters {
+    spawned: AtomicUsize,
+    completed: AtomicUsize,
+    polls: AtomicUsize,
+    // One accumulator per worker, summed when taking a snapshot, so that
+    // each lock is only ever contended by `snapshot`. Not atomics, as a
+    // `usize` of nanoseconds would overflow quickly on 32-bit targets.
+    poll_time: Vec<Mutex<Duration>>,
+    wakeups: AtomicUsize,
+}
+
+impl Counters {
+    pub(crate) fn new(workers: usize) -> Counters {
+        Counters {
+            spawned: AtomicUsize::new(0),
+            completed: AtomicUsize::new(0),
+            polls: AtomicUsize::new(0),
+            poll_time: (0..workers).map(|_| Mutex::new(Duration::from_secs(0))).collect(),
+            wakeups: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn spawned(&self, n: usize) {
+        self.spawned.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub(crate) fn completed(&self) {
+        self.completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // Record a poll of a task on worker `idx` which started at `start`.
+    pub(crate) fn polled(&self, idx: usize, start: Instant) {
+        let elapsed = start.elapsed();
+        self.polls.fetch_add(1, Ordering::Relaxed);
+        *self.poll_time[idx].lock().unwrap() += elapsed;
+    }
+
+    pub(crate) fn woken(&self) {
+        self.wakeups.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> Metrics {
+        Metrics {
+            spawned: self.spawned.load(Ordering::Relaxed),
+            completed: self.completed.load(Ordering::Relaxed),
+            polls: self.polls.load(Ordering::Relaxed),
+            poll_time: self.poll_time.iter()
+                .fold(Duration::from_secs(0), |sum, time| sum + *time.lock().unwrap()),
+            wakeups: self.wakeups.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,16 +1,24 @@
+use crate::blocking::{self, BlockingPool};
 use crate::enter;
+use crate::join_handle::{join_handle, JoinHandle};
+use crate::metrics::{Counters, Metrics};
 use crate::unpark_mutex::UnparkMutex;
+use crossbeam_deque::{Injector, Steal, Stealer, Worker};
 use futures_core::future::{Future, FutureObj};
-use futures_core::task::{Poll, Wake, Spawn, SpawnError};
-use futures_util::future::FutureExt;
+use futures_core::task::{LocalWaker, Poll, Wake, Waker, Spawn, SpawnError};
+use futures_util::future::{self, FutureExt};
 use futures_util::task::local_waker_ref_from_nonlocal;
 use num_cpus;
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::io;
+use std::mem;
+use std::pin::Pin;
 use std::prelude::v1::*;
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::mpsc;
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
+use std::time::{Duration, Instant};
 use std::fmt;
 
 /// A general-purpose thread pool for scheduling tasks that poll futures to
@@ -19,6 +27,15 @@ use std::fmt;
 /// The thread pool multiplexes any number of tasks onto a fixed number of
 /// worker threads.
 ///
+/// Each worker has its own queue of runnable tasks. Tasks woken from a worker
+/// thread are queued on that worker, while tasks spawned or woken from
+/// elsewhere go through a queue shared by the whole pool. Workers which run
+/// out of tasks steal from the queues of the other workers.
+///
+/// Blocking work should not be done by the tasks themselves, as it holds up
+/// their worker. It can be offloaded to a separate set of threads with
+/// [`spawn_blocking`](ThreadPool::spawn_blocking) instead.
+///
 /// This type is a clonable handle to the threadpool itself.
 /// Cloning it will only create a new reference, not a new threadpool.
 pub struct ThreadPool {
@@ -32,16 +49,79 @@ pub struct ThreadPoolBuilder {
     name_prefix: Option<String>,
     after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
     before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    on_task_spawn: Option<Arc<dyn Fn() + Send + Sync>>,
+    before_poll: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    after_poll: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    max_blocking_threads: usize,
+    blocking_keep_alive: Duration,
 }
 
 trait AssertSendSync: Send + Sync {}
 impl AssertSendSync for ThreadPool {}
 
+/// A handle to a thread pool which is shutting down, returned from
+/// [`ThreadPool::shutdown`](ThreadPool::shutdown).
+///
+/// The handle can be used either as a future, or by blocking the current
+/// thread with [`wait`](Shutdown::wait) or
+/// [`wait_timeout`](Shutdown::wait_timeout). Either way, it completes once
+/// the worker threads of the pool have exited and been joined.
+///
+/// Neither the future nor the blocking methods should be driven from a task
+/// running on the pool being shut down, as that task would then never
+/// complete.
+pub struct Shutdown {
+    state: Arc<PoolState>,
+}
+
 struct PoolState {
-    tx: Mutex<mpsc::Sender<Message>>,
-    rx: Mutex<mpsc::Receiver<Message>>,
+    // Runnable tasks scheduled from outside of the worker threads.
+    injector: Injector<Task>,
+    // Handles to the queue of runnable tasks local to each worker, which is
+    // owned by the worker thread. Idle workers steal from the queues of the
+    // others through these.
+    stealers: Vec<Stealer<Task>>,
+    // Total number of tasks sitting in `injector` and the local queues.
+    queued: AtomicUsize,
+    // Number of workers blocked on `sleep_cv` waiting for tasks.
+    sleepers: AtomicUsize,
+    sleep: Mutex<()>,
+    sleep_cv: Condvar,
+
     cnt: AtomicUsize,
     size: usize,
+
+    // Every task which has not completed, so that the ones left waiting on a
+    // wakeup can be dropped after a forced shutdown. Sharded by task id to
+    // spread out contention.
+    registry: Vec<Mutex<HashMap<usize, Weak<WakeHandle>>>>,
+    next_id: AtomicUsize,
+
+    // Set by `ThreadPool::shutdown`, after which no new tasks are accepted.
+    is_shutdown: AtomicBool,
+    // Set once the workers have been told to exit.
+    is_closed: AtomicBool,
+    // Number of spawned tasks which have not run to completion yet.
+    alive: AtomicUsize,
+    // Number of worker threads which have not exited yet.
+    workers: AtomicUsize,
+    threads: Mutex<Vec<thread::JoinHandle<()>>>,
+    // Tracks the exit of the last worker, for `Shutdown`.
+    exited: Mutex<Exited>,
+    exited_cv: Condvar,
+
+    // Runs the closures passed to `spawn_blocking`.
+    blocking: BlockingPool,
+
+    counters: Counters,
+    on_task_spawn: Option<Arc<dyn Fn() + Send + Sync>>,
+    before_poll: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+    after_poll: Option<Arc<dyn Fn(usize) + Send + Sync>>,
+}
+
+struct Exited {
+    done: bool,
+    wakers: Vec<Waker>,
 }
 
 impl fmt::Debug for ThreadPool {
@@ -57,15 +137,22 @@ impl fmt::Debug for ThreadPoolBuilder {
         f.debug_struct("ThreadPoolBuilder")
             .field("pool_size", &self.pool_size)
             .field("name_prefix", &self.name_prefix)
+            .field("max_blocking_threads", &self.max_blocking_threads)
             .finish()
     }
 }
 
-enum Message {
-    Run(Task),
-    Close,
+thread_local! {
+    // The pool and local queue of the worker running on this thread, if any.
+    // The queue lives on the stack of `PoolState::work`, which clears this
+    // before returning.
+    static CURRENT_WORKER: Cell<Option<(*const PoolState, *const Worker<Task>)>> = Cell::new(None);
 }
 
+// How often a worker checks the shared queue before its own, so that tasks
+// scheduled from outside of the pool are not starved by busy workers.
+const INJECTOR_INTERVAL: usize = 61;
+
 impl ThreadPool {
     /// Creates a new thread pool with the default configuration.
     ///
@@ -97,6 +184,121 @@ impl ThreadPool {
     pub fn run<F: Future>(&mut self, f: F) -> F::Output {
         crate::LocalPool::new().run_until(f)
     }
+
+    /// Spawns a task that polls the given future to completion, returning a
+    /// [`JoinHandle`](crate::JoinHandle) to its output.
+    ///
+    /// Unlike the handle returned by
+    /// [`SpawnExt::spawn_with_handle`](futures_util::task::SpawnExt::spawn_with_handle),
+    /// dropping a `JoinHandle` does not cancel the task; it can be stopped
+    /// explicitly with [`abort`](crate::JoinHandle::abort). If the task
+    /// panics, the panic is caught and reported through the handle.
+    ///
+    /// ```
+    /// #![feature(async_await, futures_api)]
+    /// use futures::executor::{block_on, ThreadPool};
+    ///
+    /// let mut pool = ThreadPool::new().unwrap();
+    /// let handle = pool.spawn_handle(async { 1 + 2 }).unwrap();
+    /// assert_eq!(block_on(handle).unwrap(), 3);
+    /// ```
+    pub fn spawn_handle<Fut>(&mut self, future: Fut) -> Result<JoinHandle<Fut::Output>, SpawnError>
+        where Fut: Future + Send + 'static,
+              Fut::Output: Send + 'static,
+    {
+        let (task, handle) = join_handle(future);
+        self.spawn_obj(FutureObj::new(Box::new(task)))?;
+        Ok(handle)
+    }
+
+    /// Runs the blocking closure `f` on a separate thread, returning a
+    /// [`JoinHandle`](crate::JoinHandle) to its result.
+    ///
+    /// Tasks polled by the pool should not block, as a blocking task holds
+    /// up the worker running it. Blocking I/O, such as calls into `std::fs`,
+    /// and long computations should be offloaded with this method instead.
+    ///
+    /// The closures run on threads spawned on demand, up to
+    /// [`max_blocking_threads`](ThreadPoolBuilder::max_blocking_threads) of
+    /// them; further closures are queued until a thread becomes available.
+    /// Threads exit after being idle for
+    /// [`blocking_keep_alive`](ThreadPoolBuilder::blocking_keep_alive).
+    ///
+    /// Closures which have not completed count as running tasks for
+    /// [`shutdown`](ThreadPool::shutdown). If `f` panics, the panic is caught
+    /// and reported through the handle.
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// use futures::executor::{block_on, ThreadPool};
+    ///
+    /// let pool = ThreadPool::new().unwrap();
+    /// let handle = pool.spawn_blocking(|| {
+    ///     std::fs::read_to_string("Cargo.toml")
+    /// }).unwrap();
+    ///
+    /// block_on(async {
+    ///     let contents = await!(handle).unwrap();
+    ///     assert!(contents.is_ok());
+    /// });
+    /// ```
+    pub fn spawn_blocking<F, R>(&self, f: F) -> Result<JoinHandle<R>, SpawnError>
+        where F: FnOnce() -> R + Send + 'static,
+              R: Send + 'static,
+    {
+        // Counted the same way as `spawn_obj`.
+        self.state.alive.fetch_add(1, Ordering::SeqCst);
+        if self.state.is_shutdown.load(Ordering::SeqCst) {
+            self.state.task_done();
+            return Err(SpawnError::shutdown());
+        }
+
+        // The closure is dropped once it has run, or when it is aborted or
+        // the job is dropped without running, which marks it as done.
+        let done = BlockingDone(self.clone());
+        let (task, handle) = join_handle(future::lazy(move |_| {
+            let _done = done;
+            f()
+        }));
+        self.state.blocking.spawn(FutureObj::new(Box::new(task)));
+        Ok(handle)
+    }
+
+    /// Returns a snapshot of the counters kept by this thread pool.
+    pub fn metrics(&self) -> Metrics {
+        self.state.counters.snapshot()
+    }
+
+    /// Begins a graceful shutdown of this thread pool.
+    ///
+    /// After this is called, spawning onto the pool, through this handle or
+    /// any of its clones, fails with
+    /// [`SpawnError::shutdown`](futures_core::task::SpawnError::shutdown).
+    /// Tasks which were already spawned keep running, and once all of them
+    /// have completed the worker threads exit.
+    ///
+    /// The returned [`Shutdown`](Shutdown) handle can be used to wait for
+    /// the worker threads to exit, optionally with a deadline after which
+    /// the remaining tasks are abandoned.
+    ///
+    /// ```
+    /// #![feature(async_await, futures_api)]
+    /// use futures::executor::ThreadPool;
+    /// use futures::task::SpawnExt;
+    ///
+    /// let mut pool = ThreadPool::new().unwrap();
+    /// pool.spawn(async { /* ... */ }).unwrap();
+    ///
+    /// pool.shutdown().wait();
+    /// assert!(pool.spawn(async {}).is_err());
+    /// ```
+    pub fn shutdown(&self) -> Shutdown {
+        self.state.is_shutdown.store(true, Ordering::SeqCst);
+        if self.state.alive.load(Ordering::SeqCst) == 0 {
+            self.state.close();
+        }
+        Shutdown { state: self.state.clone() }
+    }
 }
 
 impl Spawn for ThreadPool {
@@ -106,6 +308,10 @@ impl Spawn for ThreadPool {
     ) -> Result<(), SpawnError> {
         (&*self).spawn_obj(future)
     }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        (&*self).status()
+    }
 }
 
 impl Spawn for &ThreadPool {
@@ -113,38 +319,247 @@ impl Spawn for &ThreadPool {
         &mut self,
         future: FutureObj<'static, ()>,
     ) -> Result<(), SpawnError> {
+        // Count the task before checking the flag, so that `shutdown` either
+        // sees it as alive or we see the pool as shut down.
+        self.state.alive.fetch_add(1, Ordering::SeqCst);
+        if self.state.is_shutdown.load(Ordering::SeqCst) {
+            self.state.task_done();
+            return Err(SpawnError::shutdown());
+        }
+
+        let id = self.state.next_id.fetch_add(1, Ordering::Relaxed);
+        let wake_handle = Arc::new(WakeHandle {
+            id,
+            exec: self.clone(),
+            mutex: UnparkMutex::new(),
+        });
+        self.state.register(&wake_handle);
         let task = Task {
             future,
-            wake_handle: Arc::new(WakeHandle {
-                exec: self.clone(),
-                mutex: UnparkMutex::new(),
-            }),
+            wake_handle,
             exec: self.clone(),
         };
-        self.state.send(Message::Run(task));
+        self.state.counters.spawned(1);
+        if let Some(on_task_spawn) = &self.state.on_task_spawn {
+            on_task_spawn();
+        }
+        self.state.schedule(task);
         Ok(())
     }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        if self.state.is_shutdown.load(Ordering::SeqCst) {
+            Err(SpawnError::shutdown())
+        } else {
+            Ok(())
+        }
+    }
 }
 
 impl PoolState {
-    fn send(&self, msg: Message) {
-        self.tx.lock().unwrap().send(msg).unwrap();
+    // Queue a runnable task, on the current worker if we're running on one of
+    // this pool's threads and on the shared queue otherwise.
+    fn schedule(&self, task: Task) {
+        let local = CURRENT_WORKER.with(|current| current.get())
+            .and_then(|(pool, local)| {
+                if pool == self as *const PoolState { Some(local) } else { None }
+            });
+
+        // Bump the count first so that it never underflows when a worker
+        // takes the task before we get to it.
+        self.queued.fetch_add(1, Ordering::SeqCst);
+        match local {
+            // Safety: `CURRENT_WORKER` only points at the queue while it is
+            // alive, and the queue is only used from its own thread.
+            Some(local) => unsafe { (*local).push(task) },
+            None => self.injector.push(task),
+        }
+
+        // A worker registers itself as a sleeper before checking `queued`, so
+        // either it sees our task or we see it sleeping. Taking the lock
+        // ensures it is actually waiting on the condvar before we notify.
+        if self.sleepers.load(Ordering::SeqCst) > 0 {
+            let _sleep = self.sleep.lock().unwrap();
+            self.sleep_cv.notify_one();
+        }
+    }
+
+    // Find a runnable task for worker `idx`, whose queue is `local`,
+    // stealing one if necessary.
+    fn next_task(&self, local: &Worker<Task>, idx: usize, tick: usize) -> Option<Task> {
+        let task = if tick % INJECTOR_INTERVAL == 0 {
+            self.steal_injector(local).or_else(|| local.pop())
+        } else {
+            local.pop().or_else(|| self.steal_injector(local))
+        };
+        let task = task.or_else(|| self.steal(local, idx));
+        if task.is_some() {
+            self.queued.fetch_sub(1, Ordering::SeqCst);
+        }
+        task
+    }
+
+    // Move a batch of tasks from the shared queue onto `local`, returning one
+    // of them.
+    fn steal_injector(&self, local: &Worker<Task>) -> Option<Task> {
+        retry_steal(|| self.injector.steal_batch_and_pop(local))
+    }
+
+    // Steal a batch of the tasks queued on another worker, returning one of
+    // them and moving the rest onto `local`.
+    fn steal(&self, local: &Worker<Task>, idx: usize) -> Option<Task> {
+        let n = self.stealers.len();
+        (1..n)
+            .map(|i| &self.stealers[(idx + i) % n])
+            .filter_map(|victim| retry_steal(|| victim.steal_batch_and_pop(local)))
+            .next()
+    }
+
+    // Block the current worker until a task is queued or the pool is closed.
+    fn sleep(&self) {
+        let mut sleep = self.sleep.lock().unwrap();
+        self.sleepers.fetch_add(1, Ordering::SeqCst);
+        while self.queued.load(Ordering::SeqCst) == 0 &&
+            !self.is_closed.load(Ordering::SeqCst)
+        {
+            sleep = self.sleep_cv.wait(sleep).unwrap();
+        }
+        self.sleepers.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    // Tell every worker to exit once it has finished its current task. This
+    // only has an effect the first time it is called.
+    fn close(&self) {
+        if !self.is_closed.swap(true, Ordering::SeqCst) {
+            {
+                let _sleep = self.sleep.lock().unwrap();
+                self.sleep_cv.notify_all();
+            }
+            self.blocking.close();
+        }
+    }
+
+    // Poll the future of a task on worker `idx`, recording metrics and
+    // running the poll hooks.
+    fn poll_task(
+        &self,
+        idx: usize,
+        future: &mut FutureObj<'static, ()>,
+        local_waker: &LocalWaker,
+    ) -> Poll<()> {
+        if let Some(before_poll) = &self.before_poll {
+            before_poll(idx);
+        }
+        let start = Instant::now();
+        let res = future.poll_unpin(local_waker);
+        self.counters.polled(idx, start);
+        if let Some(after_poll) = &self.after_poll {
+            after_poll(idx);
+        }
+        res
+    }
+
+    fn registry_shard(&self, id: usize) -> &Mutex<HashMap<usize, Weak<WakeHandle>>> {
+        &self.registry[id % self.registry.len()]
+    }
+
+    fn register(&self, handle: &Arc<WakeHandle>) {
+        self.registry_shard(handle.id).lock().unwrap()
+            .insert(handle.id, Arc::downgrade(handle));
+    }
+
+    fn unregister(&self, id: usize) {
+        self.registry_shard(id).lock().unwrap().remove(&id);
+    }
+
+    // Drop every task which has not completed, after the workers have exited
+    // following a forced shutdown. Each task holds a handle to the pool, and
+    // a task waiting on a wakeup is only reachable from its own waker, so
+    // they would otherwise never be dropped.
+    fn abandon_tasks(&self) {
+        let mut tasks = Vec::new();
+        while let Some(task) = retry_steal(|| self.injector.steal()) {
+            tasks.push(task);
+        }
+        for stealer in &self.stealers {
+            while let Some(task) = retry_steal(|| stealer.steal()) {
+                tasks.push(task);
+            }
+        }
+        for shard in &self.registry {
+            let handles = mem::replace(&mut *shard.lock().unwrap(), HashMap::new());
+            // This also marks the tasks as complete, so they are not queued
+            // again if dropping one of the others wakes them.
+            tasks.extend(handles.values()
+                .filter_map(Weak::upgrade)
+                .filter_map(|handle| handle.mutex.take_waiting()));
+        }
+        self.queued.store(0, Ordering::SeqCst);
+
+        // Dropping the futures runs arbitrary code, so do it outside of the
+        // locks.
+        drop(tasks);
+        self.blocking.abandon();
+    }
+
+    // Called when a spawned task has run to completion, or was rejected.
+    fn task_done(&self) {
+        if self.alive.fetch_sub(1, Ordering::SeqCst) == 1 &&
+            self.is_shutdown.load(Ordering::SeqCst)
+        {
+            self.close();
+        }
+    }
+
+    fn worker_exited(&self) {
+        if self.workers.fetch_sub(1, Ordering::SeqCst) == 1 {
+            let mut exited = self.exited.lock().unwrap();
+            exited.done = true;
+            for waker in exited.wakers.drain(..) {
+                waker.wake();
+            }
+            self.exited_cv.notify_all();
+        }
     }
 
     fn work(&self,
             idx: usize,
+            local: Worker<Task>,
             after_start: Option<Arc<dyn Fn(usize) + Send + Sync>>,
             before_stop: Option<Arc<dyn Fn(usize) + Send + Sync>>) {
+        // Record the exit even if a task panics and unwinds the worker.
+        struct ExitGuard<'a>(&'a PoolState);
+        impl Drop for ExitGuard<'_> {
+            fn drop(&mut self) {
+                self.0.worker_exited();
+            }
+        }
+        let _exit = ExitGuard(self);
+
+        // Stop scheduling onto `local` before it goes away, which happens
+        // after this is dropped.
+        struct CurrentGuard;
+        impl Drop for CurrentGuard {
+            fn drop(&mut self) {
+                CURRENT_WORKER.with(|current| current.set(None));
+            }
+        }
+        let _current = CurrentGuard;
+
         let _scope = enter().unwrap();
+        CURRENT_WORKER.with(|current| {
+            current.set(Some((self as *const PoolState, &local as *const Worker<Task>)))
+        });
         if let Some(after_start) = after_start {
             after_start(idx);
         }
-        loop {
-            let msg = self.rx.lock().unwrap().recv().unwrap();
-            match msg {
-                Message::Run(task) => task.run(),
-                Message::Close => break,
+        let mut tick = 0;
+        while !self.is_closed.load(Ordering::SeqCst) {
+            match self.next_task(&local, idx, tick) {
+                Some(task) => task.run(idx),
+                None => self.sleep(),
             }
+            tick = tick.wrapping_add(1);
         }
         if let Some(before_stop) = before_stop {
             before_stop(idx);
@@ -152,6 +567,17 @@ impl PoolState {
     }
 }
 
+// Retry a steal until it either takes a task or finds the queue empty.
+fn retry_steal<F: FnMut() -> Steal<Task>>(mut steal: F) -> Option<Task> {
+    loop {
+        match steal() {
+            Steal::Success(task) => return Some(task),
+            Steal::Empty => return None,
+            Steal::Retry => {}
+        }
+    }
+}
+
 impl Clone for ThreadPool {
     fn clone(&self) -> ThreadPool {
         self.state.cnt.fetch_add(1, Ordering::Relaxed);
@@ -162,10 +588,95 @@ impl Clone for ThreadPool {
 impl Drop for ThreadPool {
     fn drop(&mut self) {
         if self.state.cnt.fetch_sub(1, Ordering::Relaxed) == 1 {
-            for _ in 0..self.state.size {
-                self.state.send(Message::Close);
+            self.state.close();
+        }
+    }
+}
+
+impl Shutdown {
+    /// Blocks the current thread until all tasks on the pool have completed
+    /// and its worker threads have exited.
+    pub fn wait(self) {
+        let mut exited = self.state.exited.lock().unwrap();
+        while !exited.done {
+            exited = self.state.exited_cv.wait(exited).unwrap();
+        }
+        drop(exited);
+        self.join();
+    }
+
+    /// Blocks the current thread until all tasks on the pool have completed
+    /// and its worker threads have exited, or until `timeout` has elapsed.
+    ///
+    /// Returns `true` if the pool shut down gracefully. Otherwise the
+    /// workers are told to exit as soon as they are done polling their
+    /// current task, and this function waits for them to do so before
+    /// returning `false`. Tasks which had not completed by then are never
+    /// polled again: they are dropped, along with the closures passed to
+    /// [`spawn_blocking`](ThreadPool::spawn_blocking) which have not started
+    /// running, and their [`JoinHandle`](crate::JoinHandle)s resolve to
+    /// [`JoinError::Cancelled`](crate::JoinError::Cancelled).
+    pub fn wait_timeout(self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut exited = self.state.exited.lock().unwrap();
+        while !exited.done {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            exited = self.state.exited_cv.wait_timeout(exited, deadline - now)
+                .unwrap().0;
+        }
+        let graceful = exited.done;
+        drop(exited);
+
+        if !graceful {
+            self.state.close();
+            let mut exited = self.state.exited.lock().unwrap();
+            while !exited.done {
+                exited = self.state.exited_cv.wait(exited).unwrap();
             }
         }
+        self.join();
+        if !graceful {
+            self.state.abandon_tasks();
+        }
+        graceful
+    }
+
+    fn join(&self) {
+        let threads = mem::replace(&mut *self.state.threads.lock().unwrap(), Vec::new());
+        for thread in threads {
+            // A worker only fails if a task panicked, which has already been
+            // reported on that thread.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Future for Shutdown {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        let mut exited = self.state.exited.lock().unwrap();
+        if exited.done {
+            drop(exited);
+            self.join();
+            Poll::Ready(())
+        } else {
+            if !exited.wakers.iter().any(|w| lw.will_wake_nonlocal(w)) {
+                exited.wakers.push(lw.clone().into_waker());
+            }
+            Poll::Pending
+        }
+    }
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("size", &self.state.size)
+            .finish()
     }
 }
 
@@ -180,6 +691,11 @@ impl ThreadPoolBuilder {
             name_prefix: None,
             after_start: None,
             before_stop: None,
+            on_task_spawn: None,
+            before_poll: None,
+            after_poll: None,
+            max_blocking_threads: 512,
+            blocking_keep_alive: Duration::from_secs(10),
         }
     }
 
@@ -242,24 +758,110 @@ impl ThreadPoolBuilder {
         self
     }
 
+    /// Execute closure `f` each time a task is spawned onto the pool.
+    ///
+    /// This hook is intended for bookkeeping and monitoring. The closure runs
+    /// on the thread spawning the task, before the task is first polled.
+    pub fn on_task_spawn<F>(&mut self, f: F) -> &mut Self
+        where F: Fn() + Send + Sync + 'static
+    {
+        self.on_task_spawn = Some(Arc::new(f));
+        self
+    }
+
+    /// Execute closure `f` on a worker thread just before it polls a task.
+    ///
+    /// This hook is intended for bookkeeping and monitoring.
+    ///
+    /// The closure provided will receive an index corresponding to the worker
+    /// thread it's running on.
+    pub fn before_poll<F>(&mut self, f: F) -> &mut Self
+        where F: Fn(usize) + Send + Sync + 'static
+    {
+        self.before_poll = Some(Arc::new(f));
+        self
+    }
+
+    /// Execute closure `f` on a worker thread just after it has polled a
+    /// task.
+    ///
+    /// This hook is intended for bookkeeping and monitoring.
+    ///
+    /// The closure provided will receive an index corresponding to the worker
+    /// thread it's running on.
+    pub fn after_poll<F>(&mut self, f: F) -> &mut Self
+        where F: Fn(usize) + Send + Sync + 'static
+    {
+        self.after_poll = Some(Arc::new(f));
+        self
+    }
+
+    /// Set the maximum number of threads running closures passed to
+    /// [`ThreadPool::spawn_blocking`](ThreadPool::spawn_blocking).
+    ///
+    /// These threads are separate from the worker threads, and are only
+    /// spawned when needed. Closures spawned while this many of them are
+    /// busy wait for one to become available. By default, this is 512.
+    pub fn max_blocking_threads(&mut self, max: usize) -> &mut Self {
+        self.max_blocking_threads = max;
+        self
+    }
+
+    /// Set how long a thread running blocking closures stays alive without
+    /// any work before exiting.
+    ///
+    /// By default, this is 10 seconds.
+    pub fn blocking_keep_alive(&mut self, keep_alive: Duration) -> &mut Self {
+        self.blocking_keep_alive = keep_alive;
+        self
+    }
+
     /// Create a [`ThreadPool`](ThreadPool) with the given configuration.
     ///
     /// # Panics
     ///
-    /// Panics if `pool_size == 0`.
+    /// Panics if `pool_size == 0` or `max_blocking_threads == 0`.
     pub fn create(&mut self) -> Result<ThreadPool, io::Error> {
-        let (tx, rx) = mpsc::channel();
+        assert!(self.pool_size > 0);
+        assert!(self.max_blocking_threads > 0);
+        let locals = (0..self.pool_size).map(|_| Worker::new_fifo()).collect::<Vec<_>>();
         let pool = ThreadPool {
             state: Arc::new(PoolState {
-                tx: Mutex::new(tx),
-                rx: Mutex::new(rx),
+                injector: Injector::new(),
+                stealers: locals.iter().map(Worker::stealer).collect(),
+                queued: AtomicUsize::new(0),
+                sleepers: AtomicUsize::new(0),
+                sleep: Mutex::new(()),
+                sleep_cv: Condvar::new(),
                 cnt: AtomicUsize::new(1),
                 size: self.pool_size,
+                registry: (0..self.pool_size).map(|_| Mutex::new(HashMap::new())).collect(),
+                next_id: AtomicUsize::new(0),
+                is_shutdown: AtomicBool::new(false),
+                is_closed: AtomicBool::new(false),
+                alive: AtomicUsize::new(0),
+                // Counted as the threads are spawned.
+                workers: AtomicUsize::new(0),
+                threads: Mutex::new(Vec::with_capacity(self.pool_size)),
+                exited: Mutex::new(Exited {
+                    done: false,
+                    wakers: Vec::new(),
+                }),
+                exited_cv: Condvar::new(),
+                blocking: BlockingPool::new(blocking::Config {
+                    max_threads: self.max_blocking_threads,
+                    keep_alive: self.blocking_keep_alive,
+                    stack_size: self.stack_size,
+                    name_prefix: self.name_prefix.clone(),
+                }),
+                counters: Counters::new(self.pool_size),
+                on_task_spawn: self.on_task_spawn.clone(),
+                before_poll: self.before_poll.clone(),
+                after_poll: self.after_poll.clone(),
             }),
         };
-        assert!(self.pool_size > 0);
 
-        for counter in 0..self.pool_size {
+        for (counter, local) in locals.into_iter().enumerate() {
             let state = pool.state.clone();
             let after_start = self.after_start.clone();
             let before_stop = self.before_stop.clone();
@@ -270,7 +872,19 @@ impl ThreadPoolBuilder {
             if self.stack_size > 0 {
                 thread_builder = thread_builder.stack_size(self.stack_size);
             }
-            thread_builder.spawn(move || state.work(counter, after_start, before_stop))?;
+            // Count the worker before it starts, so that it never sees the
+            // count drop to zero when it exits.
+            pool.state.workers.fetch_add(1, Ordering::SeqCst);
+            match thread_builder.spawn(move || state.work(counter, local, after_start, before_stop)) {
+                Ok(thread) => pool.state.threads.lock().unwrap().push(thread),
+                Err(e) => {
+                    // Bring down the workers which did start before failing.
+                    pool.state.worker_exited();
+                    pool.state.close();
+                    Shutdown { state: pool.state.clone() }.wait();
+                    return Err(e);
+                }
+            }
         }
         Ok(pool)
     }
@@ -282,6 +896,15 @@ impl Default for ThreadPoolBuilder {
     }
 }
 
+// Marks a closure passed to `spawn_blocking` as done when dropped.
+struct BlockingDone(ThreadPool);
+
+impl Drop for BlockingDone {
+    fn drop(&mut self) {
+        self.0.state.task_done();
+    }
+}
+
 /// A task responsible for polling a future to completion.
 struct Task {
     future: FutureObj<'static, ()>,
@@ -290,14 +913,15 @@ struct Task {
 }
 
 struct WakeHandle {
+    id: usize,
     mutex: UnparkMutex<Task>,
     exec: ThreadPool,
 }
 
 impl Task {
     /// Actually run the task (invoking `poll` on the future) on the current
-    /// thread.
-    pub fn run(self) {
+    /// thread, which is the worker with index `idx`.
+    pub fn run(self, idx: usize) {
         let Task { mut future, wake_handle, mut exec } = self;
         let local_waker = local_waker_ref_from_nonlocal(&wake_handle);
 
@@ -307,10 +931,21 @@ impl Task {
             wake_handle.mutex.start_poll();
 
             loop {
-                let res = future.poll_unpin(&local_waker);
+                let res = {
+                    let guard = PanicGuard { state: &exec.state, id: wake_handle.id };
+                    let res = exec.state.poll_task(idx, &mut future, &local_waker);
+                    mem::forget(guard);
+                    res
+                };
                 match res {
                     Poll::Pending => {}
-                    Poll::Ready(()) => return wake_handle.mutex.complete(),
+                    Poll::Ready(()) => {
+                        wake_handle.mutex.complete();
+                        exec.state.unregister(wake_handle.id);
+                        exec.state.counters.completed();
+                        exec.state.task_done();
+                        return
+                    }
                 }
                 let task = Task {
                     future,
@@ -329,6 +964,20 @@ impl Task {
     }
 }
 
+// Counts a task as done if polling it panics and unwinds its worker, so that
+// a graceful shutdown does not wait for it forever.
+struct PanicGuard<'a> {
+    state: &'a PoolState,
+    id: usize,
+}
+
+impl Drop for PanicGuard<'_> {
+    fn drop(&mut self) {
+        self.state.unregister(self.id);
+        self.state.task_done();
+    }
+}
+
 impl fmt::Debug for Task {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Task")
@@ -339,8 +988,9 @@ impl fmt::Debug for Task {
 
 impl Wake for WakeHandle {
     fn wake(arc_self: &Arc<Self>) {
+        arc_self.exec.state.counters.woken();
         match arc_self.mutex.notify() {
-            Ok(task) => arc_self.exec.state.send(Message::Run(task)),
+            Ok(task) => arc_self.exec.state.schedule(task),
             Err(()) => {}
         }
     }
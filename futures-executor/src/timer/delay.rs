@@ -0,0 +1,107 @@
+use futures_core::future::{Future, FusedFuture};
+use futures_core::task::{LocalWaker, Poll};
+use std::fmt;
+use std::pin::Pin;
+use std::prelude::v1::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{Entry, Timer};
+
+/// A future which completes at a specific point in time.
+///
+/// Delays created with [`Delay::new`](Delay::new) use the global timer,
+/// while those created with [`Timer::delay`](super::Timer::delay) use the
+/// given timer.
+#[must_use = "futures do nothing unless polled"]
+pub struct Delay {
+    timer: Timer,
+    deadline: Instant,
+    // Registered with the timer on the first poll.
+    entry: Option<Arc<Entry>>,
+    done: bool,
+}
+
+impl Delay {
+    /// Creates a future which completes once `dur` has elapsed on the global
+    /// timer.
+    pub fn new(dur: Duration) -> Delay {
+        Timer::global().delay(dur)
+    }
+
+    pub(super) fn new_at(timer: Timer, deadline: Instant) -> Delay {
+        Delay {
+            timer,
+            deadline,
+            entry: None,
+            done: false,
+        }
+    }
+
+    /// Returns the instant at which this future completes.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Resets this future to complete at `deadline` instead, even if it has
+    /// already completed.
+    pub fn reset(&mut self, deadline: Instant) {
+        self.deadline = deadline;
+        self.deregister();
+        self.done = false;
+    }
+
+    fn deregister(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.timer.deregister(&entry);
+        }
+    }
+
+    pub(super) fn timer(&self) -> &Timer {
+        &self.timer
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        if self.done || self.timer.now() >= self.deadline {
+            self.done = true;
+            return Poll::Ready(());
+        }
+
+        if self.entry.is_none() {
+            let entry = self.timer.register(self.deadline);
+            self.entry = Some(entry);
+        }
+        let entry = self.entry.as_ref().unwrap();
+        entry.task.register(lw);
+        if entry.is_fired() {
+            self.done = true;
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        self.deregister();
+    }
+}
+
+impl FusedFuture for Delay {
+    fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl fmt::Debug for Delay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Delay")
+            .field("deadline", &self.deadline)
+            .finish()
+    }
+}
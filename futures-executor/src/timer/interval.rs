@@ -0,0 +1,59 @@
+use futures_core::future::Future;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll};
+use futures_util::ready;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+
+use super::{Delay, Timer};
+
+/// A stream which yields at a fixed period.
+///
+/// Each item is the instant at which the interval was scheduled to fire. If
+/// the stream is not polled for a while, the missed ticks are yielded back to
+/// back, so that the stream stays aligned with its original schedule.
+///
+/// Intervals created with [`Interval::new`](Interval::new) use the global
+/// timer, while those created with
+/// [`Timer::interval`](super::Timer::interval) use the given timer.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Interval {
+    delay: Delay,
+    period: Duration,
+}
+
+impl Interval {
+    /// Creates a stream which yields every `period` on the global timer,
+    /// starting one `period` from now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(period: Duration) -> Interval {
+        Timer::global().interval(period)
+    }
+
+    pub(super) fn new_at(delay: Delay, period: Duration) -> Interval {
+        assert!(period > Duration::new(0, 0), "interval period must be non-zero");
+        Interval { delay, period }
+    }
+}
+
+impl Stream for Interval {
+    type Item = Instant;
+
+    fn poll_next(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Option<Instant>> {
+        ready!(Pin::new(&mut self.delay).poll(lw));
+        let tick = self.delay.deadline();
+        let next = tick + self.period;
+        self.delay.reset(next);
+        Poll::Ready(Some(tick))
+    }
+}
+
+impl FusedStream for Interval {
+    fn is_terminated(&self) -> bool {
+        false
+    }
+}
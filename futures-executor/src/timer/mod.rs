@@ -0,0 +1,371 @@
+//! Timers and time-related combinators.
+//!
+//! A [`Timer`] keeps track of pending deadlines and wakes the tasks waiting
+//! on them once they pass. A timer created with [`Timer::new`] runs on a
+//! dedicated background thread following the system clock, while one created
+//! with [`Timer::mock`] only advances when its [`MockClock`] is told to, which
+//! makes code using timers deterministic under test.
+//!
+//! The [`Delay`] and [`Interval`] types, and the [`FutureTimeoutExt`] and
+//! [`StreamTimeoutExt`] combinators, use a global timer by default. Methods on
+//! `Timer` create the same types driven by a specific timer instead.
+//!
+//! [`Timer`]: struct.Timer.html
+//! [`Timer::new`]: struct.Timer.html#method.new
+//! [`Timer::mock`]: struct.Timer.html#method.mock
+//! [`MockClock`]: struct.MockClock.html
+//! [`Delay`]: struct.Delay.html
+//! [`Interval`]: struct.Interval.html
+//! [`FutureTimeoutExt`]: trait.FutureTimeoutExt.html
+//! [`StreamTimeoutExt`]: trait.StreamTimeoutExt.html
+
+use futures_core::task::__internal::AtomicWaker;
+use lazy_static::lazy_static;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::prelude::v1::*;
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::{self, AtomicBool, AtomicUsize};
+use std::sync::atomic::Ordering::SeqCst;
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod delay;
+pub use self::delay::Delay;
+
+mod interval;
+pub use self::interval::Interval;
+
+mod timeout;
+pub use self::timeout::{FutureTimeoutExt, StreamTimeoutExt, TimedOut, Timeout, TimeoutStream};
+
+/// A source of time which wakes up tasks when their deadlines pass.
+///
+/// This type is a clonable handle to the timer itself. The background thread
+/// of a timer created with [`Timer::new`](Timer::new) exits once all handles
+/// to it, including the timers and intervals created from it, are dropped.
+pub struct Timer {
+    inner: Arc<Inner>,
+}
+
+/// The clock of a timer created with [`Timer::mock`](Timer::mock).
+///
+/// Time only passes for the timer when [`advance`](MockClock::advance) is
+/// called.
+#[derive(Clone)]
+pub struct MockClock {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    clock: Clock,
+    state: Mutex<State>,
+    // Wakes up the background thread when the earliest deadline changes or
+    // the timer is dropped.
+    cv: Condvar,
+    // Number of `Timer` handles in existence.
+    handles: AtomicUsize,
+}
+
+enum Clock {
+    System,
+    Mock(Mutex<Instant>),
+}
+
+struct State {
+    // Pending deadlines, earliest first. A `Delay` removes its entry when it
+    // is dropped or reset, so only live deadlines are kept.
+    entries: BTreeMap<Key, Arc<Entry>>,
+    // Used to order entries with the same deadline.
+    next_seq: u64,
+    // Set once the last handle to the timer has been dropped.
+    shutdown: bool,
+}
+
+type Key = (Instant, u64);
+
+/// A deadline registered with a timer, shared with a `Delay`.
+struct Entry {
+    key: Key,
+    fired: AtomicBool,
+    task: AtomicWaker,
+}
+
+lazy_static! {
+    static ref GLOBAL_TIMER: Timer = Timer::new()
+        .expect("Unable to create global timer");
+}
+
+impl Timer {
+    /// Creates a new timer following the system clock.
+    ///
+    /// This spawns a background thread which fires deadlines as they pass.
+    pub fn new() -> Result<Timer, io::Error> {
+        let timer = Timer::with_clock(Clock::System);
+        let inner = timer.inner.clone();
+        thread::Builder::new()
+            .name("futures-timer".to_string())
+            .spawn(move || inner.run())?;
+        Ok(timer)
+    }
+
+    /// Creates a new timer whose time only passes when the returned
+    /// [`MockClock`](MockClock) is advanced.
+    ///
+    /// No background thread is spawned; deadlines fire on the thread calling
+    /// [`MockClock::advance`](MockClock::advance).
+    ///
+    /// ```
+    /// #![feature(futures_api)]
+    /// use futures::executor::block_on;
+    /// use futures::executor::timer::Timer;
+    /// use std::time::Duration;
+    ///
+    /// let (timer, clock) = Timer::mock();
+    /// let delay = timer.delay(Duration::from_secs(60));
+    ///
+    /// // Completes right away, without a minute passing in real time.
+    /// clock.advance(Duration::from_secs(60));
+    /// block_on(delay);
+    /// ```
+    pub fn mock() -> (Timer, MockClock) {
+        let timer = Timer::with_clock(Clock::Mock(Mutex::new(Instant::now())));
+        let clock = MockClock { inner: timer.inner.clone() };
+        (timer, clock)
+    }
+
+    fn with_clock(clock: Clock) -> Timer {
+        Timer {
+            inner: Arc::new(Inner {
+                clock,
+                state: Mutex::new(State {
+                    entries: BTreeMap::new(),
+                    next_seq: 0,
+                    shutdown: false,
+                }),
+                cv: Condvar::new(),
+                handles: AtomicUsize::new(1),
+            }),
+        }
+    }
+
+    /// Returns the current time according to this timer's clock.
+    pub fn now(&self) -> Instant {
+        self.inner.now()
+    }
+
+    /// Creates a future which completes once `dur` has elapsed on this timer.
+    pub fn delay(&self, dur: Duration) -> Delay {
+        Delay::new_at(self.clone(), self.now() + dur)
+    }
+
+    /// Creates a future which completes once this timer reaches `deadline`.
+    pub fn delay_until(&self, deadline: Instant) -> Delay {
+        Delay::new_at(self.clone(), deadline)
+    }
+
+    /// Creates a stream which yields every `period` on this timer, starting
+    /// one `period` from now.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn interval(&self, period: Duration) -> Interval {
+        Interval::new_at(self.delay(period), period)
+    }
+
+    /// Wraps `future` so that it fails with [`TimedOut`](TimedOut) if it does
+    /// not complete within `dur` on this timer.
+    pub fn timeout<Fut>(&self, future: Fut, dur: Duration) -> Timeout<Fut> {
+        Timeout::new(future, self.delay(dur))
+    }
+
+    /// Wraps `stream` so that it yields a [`TimedOut`](TimedOut) error each
+    /// time no item arrives within `dur` on this timer.
+    pub fn timeout_stream<St>(&self, stream: St, dur: Duration) -> TimeoutStream<St> {
+        TimeoutStream::new(stream, self.delay(dur), dur)
+    }
+
+    fn global() -> &'static Timer {
+        &GLOBAL_TIMER
+    }
+
+    // Registers a deadline with this timer, returning the entry which will be
+    // fired once it passes.
+    fn register(&self, deadline: Instant) -> Arc<Entry> {
+        let mut state = self.inner.state.lock().unwrap();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+
+        // Checked under the lock, as the clock may be firing deadlines
+        // concurrently and would otherwise miss this one.
+        if deadline <= self.inner.now() {
+            return Arc::new(Entry {
+                key: (deadline, seq),
+                fired: AtomicBool::new(true),
+                task: AtomicWaker::new(),
+            });
+        }
+
+        let earliest = state.next_deadline()
+            .map(|next| deadline < next)
+            .unwrap_or(true);
+        let entry = Arc::new(Entry {
+            key: (deadline, seq),
+            fired: AtomicBool::new(false),
+            task: AtomicWaker::new(),
+        });
+        state.entries.insert(entry.key, entry.clone());
+        drop(state);
+
+        // The background thread may be sleeping until a later deadline.
+        if earliest {
+            self.inner.cv.notify_one();
+        }
+        entry
+    }
+
+    // Removes a deadline which is no longer waited on, if it has not fired
+    // yet.
+    fn deregister(&self, entry: &Entry) {
+        self.inner.state.lock().unwrap().entries.remove(&entry.key);
+    }
+}
+
+impl Clone for Timer {
+    fn clone(&self) -> Timer {
+        self.inner.handles.fetch_add(1, atomic::Ordering::Relaxed);
+        Timer { inner: self.inner.clone() }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        if self.inner.handles.fetch_sub(1, atomic::Ordering::Relaxed) == 1 {
+            self.inner.state.lock().unwrap().shutdown = true;
+            self.inner.cv.notify_one();
+        }
+    }
+}
+
+impl fmt::Debug for Timer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Timer")
+            .field("mock", &self.inner.is_mock())
+            .finish()
+    }
+}
+
+impl MockClock {
+    /// Returns the current time of the mock clock.
+    pub fn now(&self) -> Instant {
+        self.inner.now()
+    }
+
+    /// Advances the mock clock by `dur`, waking the tasks waiting on any
+    /// deadlines which have now passed.
+    pub fn advance(&self, dur: Duration) {
+        let now = match &self.inner.clock {
+            Clock::Mock(now) => {
+                let mut now = now.lock().unwrap();
+                *now += dur;
+                *now
+            }
+            Clock::System => unreachable!(),
+        };
+        self.inner.fire(now);
+    }
+
+    /// Returns the earliest deadline which is still pending on the timer, if
+    /// any.
+    ///
+    /// This is useful for advancing the clock straight to the next point in
+    /// time at which something happens.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.inner.state.lock().unwrap().next_deadline()
+    }
+}
+
+impl fmt::Debug for MockClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockClock")
+            .field("now", &self.now())
+            .finish()
+    }
+}
+
+impl Inner {
+    fn now(&self) -> Instant {
+        match &self.clock {
+            Clock::System => Instant::now(),
+            Clock::Mock(now) => *now.lock().unwrap(),
+        }
+    }
+
+    fn is_mock(&self) -> bool {
+        match self.clock {
+            Clock::System => false,
+            Clock::Mock(_) => true,
+        }
+    }
+
+    // Fire all entries whose deadline is at or before `now`.
+    fn fire(&self, now: Instant) {
+        let mut fired = Vec::new();
+        {
+            let mut state = self.state.lock().unwrap();
+            loop {
+                let key = match state.entries.keys().next() {
+                    Some(&key) if key.0 <= now => key,
+                    _ => break,
+                };
+                fired.push(state.entries.remove(&key).unwrap());
+            }
+        }
+
+        // Wake outside of the lock, in case a task registers a new deadline
+        // from its waker.
+        for entry in fired {
+            entry.fired.store(true, SeqCst);
+            entry.task.wake();
+        }
+    }
+
+    // The body of the background thread of a timer following the system
+    // clock.
+    fn run(&self) {
+        loop {
+            self.fire(Instant::now());
+
+            let state = self.state.lock().unwrap();
+            if state.shutdown {
+                return;
+            }
+            // Deadlines registered after this point notify the condvar, which
+            // we only release by waiting on it.
+            let now = Instant::now();
+            match state.next_deadline() {
+                Some(deadline) if deadline <= now => {}
+                Some(deadline) => {
+                    drop(self.cv.wait_timeout(state, deadline - now).unwrap());
+                }
+                None => {
+                    drop(self.cv.wait(state).unwrap());
+                }
+            }
+        }
+    }
+}
+
+impl State {
+    fn next_deadline(&self) -> Option<Instant> {
+        self.entries.keys().next().map(|&(deadline, _)| deadline)
+    }
+}
+
+impl Entry {
+    fn is_fired(&self) -> bool {
+        self.fired.load(SeqCst)
+    }
+}
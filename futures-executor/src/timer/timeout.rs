@@ -0,0 +1,172 @@
+use futures_core::future::Future;
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll};
+use pin_utils::{unsafe_pinned, unsafe_unpinned};
+use std::error::Error;
+use std::fmt;
+use std::pin::Pin;
+use std::time::Duration;
+
+use super::{Delay, Timer};
+
+/// The error returned from [`Timeout`](Timeout) and
+/// [`TimeoutStream`](TimeoutStream) when the deadline passes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "deadline has elapsed")
+    }
+}
+
+impl Error for TimedOut {
+    fn description(&self) -> &str {
+        "deadline has elapsed"
+    }
+}
+
+/// Future for the [`timeout`](FutureTimeoutExt::timeout) combinator.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Timeout<Fut> {
+    future: Fut,
+    delay: Delay,
+}
+
+impl<Fut> Timeout<Fut> {
+    unsafe_pinned!(future: Fut);
+    unsafe_unpinned!(delay: Delay);
+
+    pub(super) fn new(future: Fut, delay: Delay) -> Timeout<Fut> {
+        Timeout { future, delay }
+    }
+
+    /// Consumes this combinator, returning the underlying future.
+    pub fn into_inner(self) -> Fut {
+        self.future
+    }
+}
+
+impl<Fut: Unpin> Unpin for Timeout<Fut> {}
+
+impl<Fut: Future> Future for Timeout<Fut> {
+    type Output = Result<Fut::Output, TimedOut>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = self.as_mut().future().poll(lw) {
+            return Poll::Ready(Ok(output));
+        }
+        match Pin::new(self.as_mut().delay()).poll(lw) {
+            Poll::Ready(()) => Poll::Ready(Err(TimedOut)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// Stream for the [`timeout`](StreamTimeoutExt::timeout) combinator.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TimeoutStream<St> {
+    stream: St,
+    delay: Delay,
+    dur: Duration,
+}
+
+impl<St> TimeoutStream<St> {
+    unsafe_pinned!(stream: St);
+    unsafe_unpinned!(delay: Delay);
+
+    pub(super) fn new(stream: St, delay: Delay, dur: Duration) -> TimeoutStream<St> {
+        TimeoutStream { stream, delay, dur }
+    }
+
+    /// Consumes this combinator, returning the underlying stream.
+    pub fn into_inner(self) -> St {
+        self.stream
+    }
+}
+
+impl<St: Unpin> Unpin for TimeoutStream<St> {}
+
+impl<St: Stream> Stream for TimeoutStream<St> {
+    type Item = Result<St::Item, TimedOut>;
+
+    fn poll_next(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Option<Self::Item>> {
+        // Each item, or timeout, restarts the deadline for the next one.
+        let dur = self.dur;
+        match self.as_mut().stream().poll_next(lw) {
+            Poll::Ready(Some(item)) => {
+                let delay = self.as_mut().delay();
+                let deadline = delay.timer().now() + dur;
+                delay.reset(deadline);
+                return Poll::Ready(Some(Ok(item)));
+            }
+            Poll::Ready(None) => return Poll::Ready(None),
+            Poll::Pending => {}
+        }
+
+        let delay = self.as_mut().delay();
+        match Pin::new(&mut *delay).poll(lw) {
+            Poll::Ready(()) => {
+                let deadline = delay.timer().now() + dur;
+                delay.reset(deadline);
+                Poll::Ready(Some(Err(TimedOut)))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl<St: FusedStream> FusedStream for TimeoutStream<St> {
+    fn is_terminated(&self) -> bool {
+        self.stream.is_terminated()
+    }
+}
+
+/// An extension trait for `Future`s that adds a timeout using the global
+/// timer.
+pub trait FutureTimeoutExt: Future {
+    /// Wraps this future so that it fails with [`TimedOut`](TimedOut) if it
+    /// does not complete within `dur`.
+    ///
+    /// Use [`Timer::timeout`](super::Timer::timeout) to apply a timeout using
+    /// a specific timer instead.
+    ///
+    /// ```
+    /// #![feature(futures_api)]
+    /// use futures::executor::block_on;
+    /// use futures::executor::timer::{FutureTimeoutExt, TimedOut};
+    /// use futures::future;
+    /// use std::time::Duration;
+    ///
+    /// let never = future::empty::<()>().timeout(Duration::from_millis(10));
+    /// assert_eq!(block_on(never), Err(TimedOut));
+    /// ```
+    fn timeout(self, dur: Duration) -> Timeout<Self>
+        where Self: Sized,
+    {
+        Timer::global().timeout(self, dur)
+    }
+}
+
+impl<T: ?Sized + Future> FutureTimeoutExt for T {}
+
+/// An extension trait for `Stream`s that adds a timeout between items using
+/// the global timer.
+pub trait StreamTimeoutExt: Stream {
+    /// Wraps this stream so that it yields a [`TimedOut`](TimedOut) error
+    /// each time no item arrives within `dur`.
+    ///
+    /// The deadline restarts after each item and each error, and the stream
+    /// ends when the underlying stream does. Use
+    /// [`Timer::timeout_stream`](super::Timer::timeout_stream) to apply a
+    /// timeout using a specific timer instead.
+    fn timeout(self, dur: Duration) -> TimeoutStream<Self>
+        where Self: Sized,
+    {
+        Timer::global().timeout_stream(self, dur)
+    }
+}
+
+impl<T: ?Sized + Stream> StreamTimeoutExt for T {}
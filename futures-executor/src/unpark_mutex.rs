@@ -141,4 +141,15 @@ impl<D> UnparkMutex<D> {
     pub(crate) unsafe fn complete(&self) {
         self.status.store(COMPLETE, SeqCst);
     }
+
+    /// Take the data out of the mutex if the task is waiting, moving it to the
+    /// `COMPLETE` state so that it is never notified again.
+    pub(crate) fn take_waiting(&self) -> Option<D> {
+        match self.status.compare_exchange(WAITING, COMPLETE, SeqCst, SeqCst) {
+            // SAFETY: as in `notify`, winning the transition out of `WAITING`
+            // gives us exclusive access to the data.
+            Ok(_) => unsafe { (*self.inner.get()).take() },
+            Err(_) => None,
+        }
+    }
 }
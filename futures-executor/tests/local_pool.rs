@@ -1,8 +1,8 @@
-#![feature(futures_api)]
+#![feature(async_await, await_macro, futures_api)]
 
 use futures::channel::oneshot;
-use futures::executor::LocalPool;
-use futures::future::{Future, lazy};
+use futures::executor::{JoinError, LocalPool};
+use futures::future::{self, Future, lazy};
 use futures::task::{LocalWaker, Poll, Spawn, LocalSpawn};
 use std::cell::{Cell, RefCell};
 use std::pin::Pin;
@@ -168,3 +168,251 @@ fn tasks_are_scheduled_fairly() {
     pool.run();
 }
 
+
+#[test]
+fn spawn_local_handle_output() {
+    let mut pool = LocalPool::new();
+    let data = Rc::new(Cell::new(1));
+    let data2 = data.clone();
+    let handle = pool.spawner().spawn_local_handle(lazy(move |_| {
+        data2.set(2);
+        data2
+    })).unwrap();
+
+    let output = pool.run_until(handle).unwrap();
+    assert!(Rc::ptr_eq(&output, &data));
+    assert_eq!(data.get(), 2);
+}
+
+#[test]
+fn spawn_local_handle_detaches_on_drop() {
+    let mut pool = LocalPool::new();
+    let ran = Rc::new(Cell::new(false));
+    let ran2 = ran.clone();
+    drop(pool.spawner().spawn_local_handle(lazy(move |_| ran2.set(true))).unwrap());
+    pool.run();
+    assert!(ran.get());
+}
+
+#[test]
+fn spawn_local_handle_abort() {
+    let mut pool = LocalPool::new();
+    let handle = pool.spawner().spawn_local_handle(pending()).unwrap();
+    handle.abort();
+    match pool.run_until(handle) {
+        Err(JoinError::Cancelled) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+    // The aborted task was dropped, so the pool is now empty.
+    pool.run();
+}
+
+#[test]
+fn spawn_local_handle_cancelled_when_pool_dropped() {
+    let pool = LocalPool::new();
+    let handle = pool.spawner().spawn_local_handle(pending()).unwrap();
+    drop(pool);
+    match futures::executor::block_on(handle) {
+        Err(JoinError::Cancelled) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn spawn_local_handle_panic() {
+    let mut pool = LocalPool::new();
+    let handle = pool.spawner()
+        .spawn_local_handle(lazy(|_| -> i32 { panic!("boom") }))
+        .unwrap();
+    match pool.run_until(handle) {
+        Err(JoinError::Panicked(payload)) => {
+            assert_eq!(*payload.downcast::<&str>().unwrap(), "boom");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn metrics() {
+    // Wakes itself once before completing.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                lw.wake();
+                Poll::Pending
+            }
+        }
+    }
+
+    let mut pool = LocalPool::new();
+    let mut spawn = pool.spawner();
+
+    spawn.spawn_local_obj(Box::pin(YieldOnce(false)).into()).unwrap();
+    spawn.spawn_local_obj(Box::pin(lazy(|_| ())).into()).unwrap();
+    let metrics = pool.metrics();
+    assert_eq!(metrics.spawned(), 2);
+    assert_eq!(metrics.alive(), 2);
+    assert_eq!(metrics.polls(), 0);
+
+    pool.run();
+    let metrics = pool.metrics();
+    assert_eq!(metrics.spawned(), 2);
+    assert_eq!(metrics.completed(), 2);
+    assert_eq!(metrics.alive(), 0);
+    assert_eq!(metrics.polls(), 3);
+    assert_eq!(metrics.wakeups(), 1);
+}
+
+#[test]
+fn run_until_not_starved_by_spinning_task() {
+    struct Spin;
+
+    impl Future for Spin {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+            lw.wake();
+            Poll::Pending
+        }
+    }
+
+    let mut pool = LocalPool::new();
+    pool.spawner().spawn_local_obj(Box::pin(Spin).into()).unwrap();
+    pool.run_until(future::yield_now());
+}
+
+#[test]
+fn yield_now_interleaves_tasks() {
+    let order = Rc::new(RefCell::new(Vec::new()));
+    let mut pool = LocalPool::new();
+    let mut spawn = pool.spawner();
+
+    for id in 0..2 {
+        let order = order.clone();
+        spawn.spawn_local_obj(Box::pin(async move {
+            for _ in 0..3 {
+                order.borrow_mut().push(id);
+                await!(future::yield_now());
+            }
+        }).into()).unwrap();
+    }
+    pool.run();
+
+    assert_eq!(*order.borrow(), vec![0, 1, 0, 1, 0, 1]);
+}
+
+#[test]
+fn poll_budget_limits_turns() {
+    let polled = Rc::new(Cell::new(0));
+    let mut pool = LocalPool::new();
+    pool.set_poll_budget(2);
+    let mut spawn = pool.spawner();
+
+    for _ in 0..5 {
+        let polled = polled.clone();
+        spawn.spawn_local_obj(Box::pin(lazy(move |_| {
+            polled.set(polled.get() + 1);
+        })).into()).unwrap();
+    }
+
+    // The driven future sees the pool's progress after each turn.
+    let seen = RefCell::new(Vec::new());
+    pool.run_until(future::poll_fn(|lw| {
+        seen.borrow_mut().push(polled.get());
+        if polled.get() == 5 {
+            Poll::Ready(())
+        } else {
+            lw.wake();
+            Poll::Pending
+        }
+    }));
+    assert_eq!(seen.into_inner(), vec![0, 2, 4, 5]);
+}
+
+#[test]
+fn try_run_one_polls_single_task() {
+    let cnt = Rc::new(Cell::new(0));
+    let mut pool = LocalPool::new();
+    let mut spawn = pool.spawner();
+
+    for _ in 0..3 {
+        let cnt = cnt.clone();
+        spawn.spawn_local_obj(Box::pin(lazy(move |_| {
+            cnt.set(cnt.get() + 1);
+        })).into()).unwrap();
+    }
+
+    assert!(pool.try_run_one());
+    assert_eq!(cnt.get(), 1);
+    assert!(pool.try_run_one());
+    assert_eq!(cnt.get(), 2);
+    assert!(pool.try_run_one());
+    assert_eq!(cnt.get(), 3);
+    assert!(!pool.try_run_one());
+}
+
+#[test]
+fn try_run_one_returns_false_when_tasks_are_waiting() {
+    let mut pool = LocalPool::new();
+    let (tx, rx) = oneshot::channel::<()>();
+    let done = Rc::new(Cell::new(false));
+    let done2 = done.clone();
+    pool.spawner().spawn_local_obj(Box::pin(async move {
+        await!(rx).unwrap();
+        done2.set(true);
+    }).into()).unwrap();
+
+    assert!(pool.try_run_one());
+    assert!(!pool.try_run_one());
+    assert!(!done.get());
+
+    tx.send(()).unwrap();
+    assert!(pool.try_run_one());
+    assert!(done.get());
+    assert!(!pool.try_run_one());
+}
+
+#[test]
+fn run_until_stalled_runs_all_ready_tasks() {
+    let mut pool = LocalPool::new();
+    let mut spawn = pool.spawner();
+    let (tx, rx) = oneshot::channel::<()>();
+    let steps = Rc::new(RefCell::new(Vec::new()));
+
+    let steps2 = steps.clone();
+    let mut inner_spawn = spawn.clone();
+    spawn.spawn_local_obj(Box::pin(async move {
+        steps2.borrow_mut().push("outer");
+        let steps3 = steps2.clone();
+        inner_spawn.spawn_local_obj(Box::pin(async move {
+            steps3.borrow_mut().push("inner");
+        }).into()).unwrap();
+        await!(rx).unwrap();
+        steps2.borrow_mut().push("woken");
+    }).into()).unwrap();
+
+    pool.run_until_stalled();
+    assert_eq!(*steps.borrow(), vec!["outer", "inner"]);
+
+    // Stalling again makes no progress.
+    pool.run_until_stalled();
+    assert_eq!(steps.borrow().len(), 2);
+
+    tx.send(()).unwrap();
+    pool.run_until_stalled();
+    assert_eq!(*steps.borrow(), vec!["outer", "inner", "woken"]);
+}
+
+#[test]
+fn run_until_stalled_on_empty_pool() {
+    let mut pool = LocalPool::new();
+    pool.run_until_stalled();
+    assert!(!pool.try_run_one());
+}
@@ -0,0 +1,271 @@
+#![feature(futures_api)]
+
+use futures::channel::oneshot;
+use futures::executor::{block_on, JoinError, ThreadPool};
+use futures::future::{self, FutureExt};
+use futures::task::{Spawn, SpawnExt};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn shutdown_rejects_spawns() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let mut clone = pool.clone();
+    let shutdown = pool.shutdown();
+
+    assert!(pool.status().unwrap_err().is_shutdown());
+    assert!(pool.spawn(future::ready(())).unwrap_err().is_shutdown());
+    assert!(clone.spawn(future::ready(())).unwrap_err().is_shutdown());
+    shutdown.wait();
+}
+
+#[test]
+fn shutdown_waits_for_running_tasks() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let done = Arc::new(AtomicUsize::new(0));
+    let (tx, rx) = oneshot::channel::<()>();
+
+    for _ in 0..4 {
+        let done = done.clone();
+        pool.spawn(future::lazy(move |_| {
+            thread::sleep(Duration::from_millis(10));
+            done.fetch_add(1, Ordering::SeqCst);
+        })).unwrap();
+    }
+    let waiter = done.clone();
+    pool.spawn(rx.map(move |_| {
+        waiter.fetch_add(1, Ordering::SeqCst);
+    })).unwrap();
+
+    let shutdown = pool.shutdown();
+    tx.send(()).unwrap();
+    block_on(shutdown);
+    assert_eq!(done.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn shutdown_joins_workers() {
+    let stopped = Arc::new(AtomicUsize::new(0));
+    let stopped2 = stopped.clone();
+    let pool = ThreadPool::builder()
+        .pool_size(3)
+        .before_stop(move |_| { stopped2.fetch_add(1, Ordering::SeqCst); })
+        .create()
+        .unwrap();
+
+    assert!(pool.shutdown().wait_timeout(Duration::from_secs(10)));
+    assert_eq!(stopped.load(Ordering::SeqCst), 3);
+}
+
+#[test]
+fn shutdown_deadline_abandons_pending_tasks() {
+    let mut pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let (tx, rx) = oneshot::channel::<()>();
+    pool.spawn(rx.map(|_| ())).unwrap();
+
+    assert!(!pool.shutdown().wait_timeout(Duration::from_millis(10)));
+    drop(tx);
+}
+
+#[test]
+fn shutdown_deadline_cancels_pending_handles() {
+    let mut pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let (tx, rx) = oneshot::channel::<()>();
+    let handle = pool.spawn_handle(rx).unwrap();
+
+    assert!(!pool.shutdown().wait_timeout(Duration::from_millis(10)));
+    match block_on(handle) {
+        Err(JoinError::Cancelled) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+    // The abandoned task has been dropped along with its receiver.
+    assert!(tx.is_canceled());
+}
+
+#[test]
+fn shutdown_after_task_panics() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    pool.spawn(future::lazy(|_| panic!("boom"))).unwrap();
+
+    assert!(pool.shutdown().wait_timeout(Duration::from_secs(10)));
+}
+
+#[test]
+fn tasks_spawned_from_workers_all_run() {
+    const NUM: usize = 100;
+
+    let mut pool = ThreadPool::builder().pool_size(4).create().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut inner = pool.clone();
+    pool.spawn(future::lazy(move |_| {
+        // These are all queued on the current worker, so the other workers
+        // only get to run them by stealing.
+        for i in 0..NUM {
+            let tx = tx.clone();
+            inner.spawn(future::lazy(move |_| {
+                tx.send(i).unwrap();
+            })).unwrap();
+        }
+    })).unwrap();
+
+    let mut seen = rx.iter().take(NUM).collect::<Vec<_>>();
+    seen.sort();
+    assert_eq!(seen, (0..NUM).collect::<Vec<_>>());
+    pool.shutdown().wait();
+}
+
+#[test]
+fn spawn_handle_output() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let handle = pool.spawn_handle(future::lazy(|_| 1 + 2)).unwrap();
+    assert_eq!(block_on(handle).unwrap(), 3);
+}
+
+#[test]
+fn spawn_handle_detaches_on_drop() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let (tx, rx) = oneshot::channel();
+    drop(pool.spawn_handle(future::lazy(move |_| tx.send(5).unwrap())).unwrap());
+    assert_eq!(block_on(rx), Ok(5));
+}
+
+#[test]
+fn spawn_handle_abort() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let (_tx, rx) = oneshot::channel::<()>();
+    let handle = pool.spawn_handle(rx).unwrap();
+    handle.abort();
+    match block_on(handle) {
+        Err(JoinError::Cancelled) => {}
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn spawn_handle_panic() {
+    let mut pool = ThreadPool::builder().pool_size(2).create().unwrap();
+    let handle = pool.spawn_handle(future::lazy(|_| -> i32 { panic!("boom") })).unwrap();
+    match block_on(handle) {
+        Err(JoinError::Panicked(payload)) => {
+            assert_eq!(*payload.downcast::<&str>().unwrap(), "boom");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+
+    // The worker survived the panic.
+    let handle = pool.spawn_handle(future::lazy(|_| 4)).unwrap();
+    assert_eq!(block_on(handle).unwrap(), 4);
+}
+
+#[test]
+fn metrics_and_hooks() {
+    let spawns = Arc::new(AtomicUsize::new(0));
+    let before = Arc::new(AtomicUsize::new(0));
+    let after = Arc::new(AtomicUsize::new(0));
+    let (spawns2, before2, after2) = (spawns.clone(), before.clone(), after.clone());
+    let mut pool = ThreadPool::builder()
+        .pool_size(2)
+        .on_task_spawn(move || { spawns2.fetch_add(1, Ordering::SeqCst); })
+        .before_poll(move |_| { before2.fetch_add(1, Ordering::SeqCst); })
+        .after_poll(move |_| { after2.fetch_add(1, Ordering::SeqCst); })
+        .create()
+        .unwrap();
+
+    let (tx, rx) = oneshot::channel::<()>();
+    let waiting = pool.spawn_handle(rx).unwrap();
+    let done = pool.spawn_handle(future::lazy(|_| ())).unwrap();
+    block_on(done).unwrap();
+    assert_eq!(pool.metrics().spawned(), 2);
+
+    tx.send(()).unwrap();
+    block_on(waiting).unwrap().unwrap();
+    pool.shutdown().wait();
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.spawned(), 2);
+    assert_eq!(metrics.completed(), 2);
+    assert_eq!(metrics.alive(), 0);
+    assert!(metrics.wakeups() >= 1);
+    assert_eq!(metrics.polls(), before.load(Ordering::SeqCst));
+    assert_eq!(metrics.polls(), after.load(Ordering::SeqCst));
+    assert!(metrics.polls() >= 3);
+    assert_eq!(spawns.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn spawn_blocking_output() {
+    let pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let handle = pool.spawn_blocking(|| 1 + 2).unwrap();
+    assert_eq!(block_on(handle).unwrap(), 3);
+}
+
+#[test]
+fn spawn_blocking_does_not_hold_up_workers() {
+    let mut pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    // This would never complete if it ran on the only worker, which has to
+    // run the task below.
+    let blocking = pool.spawn_blocking(move || rx.recv().unwrap()).unwrap();
+    pool.spawn(future::lazy(move |_| tx.send(7).unwrap())).unwrap();
+    assert_eq!(block_on(blocking).unwrap(), 7);
+}
+
+#[test]
+fn spawn_blocking_max_threads() {
+    let pool = ThreadPool::builder()
+        .pool_size(1)
+        .max_blocking_threads(2)
+        .create()
+        .unwrap();
+    // The number of closures running, and the most seen at once.
+    let running = Arc::new(Mutex::new((0, 0)));
+
+    let handles = (0..6).map(|_| {
+        let running = running.clone();
+        pool.spawn_blocking(move || {
+            {
+                let mut running = running.lock().unwrap();
+                running.0 += 1;
+                running.1 = running.1.max(running.0);
+            }
+            thread::sleep(Duration::from_millis(10));
+            running.lock().unwrap().0 -= 1;
+        }).unwrap()
+    }).collect::<Vec<_>>();
+
+    for handle in handles {
+        block_on(handle).unwrap();
+    }
+    assert!(running.lock().unwrap().1 <= 2);
+}
+
+#[test]
+fn spawn_blocking_panic() {
+    let pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let handle = pool.spawn_blocking(|| -> i32 { panic!("boom") }).unwrap();
+    match block_on(handle) {
+        Err(JoinError::Panicked(payload)) => {
+            assert_eq!(*payload.downcast::<&str>().unwrap(), "boom");
+        }
+        res => panic!("unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn shutdown_waits_for_blocking() {
+    let pool = ThreadPool::builder().pool_size(1).create().unwrap();
+    let done = Arc::new(AtomicUsize::new(0));
+    let done2 = done.clone();
+    drop(pool.spawn_blocking(move || {
+        thread::sleep(Duration::from_millis(10));
+        done2.fetch_add(1, Ordering::SeqCst);
+    }).unwrap());
+
+    let shutdown = pool.shutdown();
+    assert!(pool.spawn_blocking(|| ()).unwrap_err().is_shutdown());
+    shutdown.wait();
+    assert_eq!(done.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,165 @@
+#![feature(futures_api)]
+
+use futures::channel::oneshot;
+use futures::executor::{block_on, block_on_stream, LocalPool};
+use futures::executor::timer::{Delay, FutureTimeoutExt, Interval, StreamTimeoutExt, TimedOut, Timer};
+use futures::future::{self, FutureExt};
+use futures::stream::{self, StreamExt};
+use futures::task::Poll;
+use std::time::{Duration, Instant};
+
+#[test]
+fn delay_waits_for_mock_clock() {
+    let (timer, clock) = Timer::mock();
+    let start = clock.now();
+    let mut delay = timer.delay(Duration::from_secs(5));
+    assert_eq!(delay.deadline(), start + Duration::from_secs(5));
+
+    let mut pool = LocalPool::new();
+    let mut poll = || pool.run_until(future::poll_fn(|lw| Poll::Ready(delay.poll_unpin(lw))));
+
+    assert_eq!(poll(), Poll::Pending);
+    clock.advance(Duration::from_secs(4));
+    assert_eq!(poll(), Poll::Pending);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(poll(), Poll::Ready(()));
+}
+
+#[test]
+fn dropped_and_reset_delays_are_removed() {
+    let (timer, clock) = Timer::mock();
+    let start = clock.now();
+    let mut delay = timer.delay(Duration::from_secs(5));
+    let mut pool = LocalPool::new();
+    let mut poll = |delay: &mut Delay| {
+        pool.run_until(future::poll_fn(|lw| Poll::Ready(delay.poll_unpin(lw))))
+    };
+
+    assert_eq!(poll(&mut delay), Poll::Pending);
+    assert_eq!(clock.next_deadline(), Some(start + Duration::from_secs(5)));
+
+    delay.reset(start + Duration::from_secs(10));
+    assert_eq!(poll(&mut delay), Poll::Pending);
+    assert_eq!(clock.next_deadline(), Some(start + Duration::from_secs(10)));
+
+    drop(delay);
+    assert_eq!(clock.next_deadline(), None);
+}
+
+#[test]
+fn delay_wakes_task_when_clock_advances() {
+    let (timer, clock) = Timer::mock();
+    let (tx, rx) = oneshot::channel();
+    let mut pool = LocalPool::new();
+    let mut spawner = pool.spawner();
+
+    let delay = timer.delay(Duration::from_millis(100));
+    let handle = spawner.spawn_local_handle(delay.map(move |()| tx.send(()).unwrap())).unwrap();
+    pool.run_until_stalled();
+
+    clock.advance(Duration::from_millis(100));
+    assert!(pool.try_run_one());
+    assert_eq!(block_on(rx), Ok(()));
+    pool.run_until(handle).unwrap();
+}
+
+#[test]
+fn delay_reset() {
+    let (timer, clock) = Timer::mock();
+    let mut delay = timer.delay(Duration::from_secs(1));
+    let mut pool = LocalPool::new();
+
+    clock.advance(Duration::from_secs(1));
+    pool.run_until(&mut delay);
+
+    delay.reset(clock.now() + Duration::from_secs(1));
+    let mut poll = || pool.run_until(future::poll_fn(|lw| Poll::Ready(delay.poll_unpin(lw))));
+    assert_eq!(poll(), Poll::Pending);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(poll(), Poll::Ready(()));
+}
+
+#[test]
+fn interval_ticks() {
+    let (timer, clock) = Timer::mock();
+    let start = clock.now();
+    let period = Duration::from_secs(2);
+    let mut interval = timer.interval(period);
+    let mut pool = LocalPool::new();
+    let mut poll = || pool.run_until(future::poll_fn(|lw| Poll::Ready(interval.poll_next_unpin(lw))));
+
+    assert_eq!(poll(), Poll::Pending);
+    clock.advance(period);
+    assert_eq!(poll(), Poll::Ready(Some(start + period)));
+    assert_eq!(poll(), Poll::Pending);
+
+    // Missed ticks are yielded back to back.
+    clock.advance(period * 2);
+    assert_eq!(poll(), Poll::Ready(Some(start + period * 2)));
+    assert_eq!(poll(), Poll::Ready(Some(start + period * 3)));
+    assert_eq!(poll(), Poll::Pending);
+}
+
+#[test]
+fn timeout_with_mock_clock() {
+    let (timer, clock) = Timer::mock();
+    let (tx, rx) = oneshot::channel::<i32>();
+    let mut pool = LocalPool::new();
+
+    let mut ok = timer.timeout(future::ready(1), Duration::from_secs(1));
+    assert_eq!(pool.run_until(&mut ok), Ok(1));
+
+    let mut timeout = timer.timeout(rx, Duration::from_secs(1));
+    let mut poll = || pool.run_until(future::poll_fn(|lw| Poll::Ready(timeout.poll_unpin(lw))));
+    assert_eq!(poll(), Poll::Pending);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(poll(), Poll::Ready(Err(TimedOut)));
+    drop(tx);
+}
+
+#[test]
+fn stream_timeout_with_mock_clock() {
+    let (timer, clock) = Timer::mock();
+    let mut pool = LocalPool::new();
+    let (tx, rx) = futures::channel::mpsc::unbounded();
+    let mut stream = timer.timeout_stream(rx, Duration::from_secs(1));
+    let mut poll = || pool.run_until(future::poll_fn(|lw| Poll::Ready(stream.poll_next_unpin(lw))));
+
+    tx.unbounded_send(1).unwrap();
+    assert_eq!(poll(), Poll::Ready(Some(Ok(1))));
+    assert_eq!(poll(), Poll::Pending);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(poll(), Poll::Ready(Some(Err(TimedOut))));
+    assert_eq!(poll(), Poll::Pending);
+    drop(tx);
+    assert_eq!(poll(), Poll::Ready(None));
+}
+
+#[test]
+fn delay_with_system_clock() {
+    let start = Instant::now();
+    block_on(Delay::new(Duration::from_millis(20)));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+}
+
+#[test]
+fn interval_with_system_clock() {
+    let start = Instant::now();
+    let ticks = block_on_stream(Interval::new(Duration::from_millis(5)))
+        .take(3)
+        .collect::<Vec<_>>();
+    assert_eq!(ticks.len(), 3);
+    assert!(start.elapsed() >= Duration::from_millis(15));
+}
+
+#[test]
+fn timeout_with_system_clock() {
+    let res = block_on(future::empty::<()>().timeout(Duration::from_millis(10)));
+    assert_eq!(res, Err(TimedOut));
+
+    let res = block_on(future::ready(3).timeout(Duration::from_secs(10)));
+    assert_eq!(res, Ok(3));
+
+    let items = block_on(stream::iter(vec![1, 2]).timeout(Duration::from_secs(10)).collect::<Vec<_>>());
+    assert_eq!(items, vec![Ok(1), Ok(2)]);
+}
@@ -1,8 +1,9 @@
 //! Asynchronous I/O
 //!
-//! This crate contains the `AsyncRead` and `AsyncWrite` traits, the
-//! asynchronous analogs to `std::io::{Read, Write}`. The primary difference is
-//! that these traits integrate with the asynchronous task system.
+//! This crate contains the `AsyncRead`, `AsyncWrite`, `AsyncSeek` and
+//! `AsyncBufRead` traits, the asynchronous analogs to
+//! `std::io::{Read, Write, Seek, BufRead}`. The primary difference is that
+//! these traits integrate with the asynchronous task system.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -28,6 +29,7 @@ mod if_std {
     pub use self::StdIo::Error as Error;
     pub use self::StdIo::ErrorKind as ErrorKind;
     pub use self::StdIo::Result as Result;
+    pub use self::StdIo::SeekFrom as SeekFrom;
 
     /// A type used to conditionally initialize buffers passed to `AsyncRead`
     /// methods, modeled after `std`.
@@ -241,6 +243,91 @@ mod if_std {
         fn poll_close(&mut self, lw: &LocalWaker) -> Poll<Result<()>>;
     }
 
+    /// Seek bytes asynchronously.
+    ///
+    /// This trait is analogous to the `std::io::Seek` trait, but integrates
+    /// with the asynchronous task system. In particular, the `poll_seek`
+    /// method, unlike `Seek::seek`, will automatically queue the current task
+    /// for wakeup and return if the object is not yet ready to seek, rather
+    /// than blocking the calling thread.
+    pub trait AsyncSeek {
+        /// Attempt to seek to an offset, in bytes, in a stream.
+        ///
+        /// A seek beyond the end of a stream is allowed, but behavior is defined
+        /// by the implementation.
+        ///
+        /// If the seek operation completed successfully,
+        /// this method returns the new position from the start of the stream.
+        /// That position can be used later with [`SeekFrom::Start`].
+        ///
+        /// # Errors
+        ///
+        /// Seeking to a negative offset is considered an error.
+        ///
+        /// # Implementation
+        ///
+        /// This function may not return errors of kind `WouldBlock` or
+        /// `Interrupted`.  Implementations must convert `WouldBlock` into
+        /// `Async::Pending` and either internally retry or convert
+        /// `Interrupted` into another error kind.
+        fn poll_seek(&mut self, lw: &LocalWaker, pos: SeekFrom)
+            -> Poll<Result<u64>>;
+    }
+
+    /// Read bytes asynchronously from a buffered source.
+    ///
+    /// This trait is analogous to the `std::io::BufRead` trait, but integrates
+    /// with the asynchronous task system. In particular, the `poll_fill_buf`
+    /// method, unlike `BufRead::fill_buf`, will automatically queue the current
+    /// task for wakeup and return if data is not yet available, rather than
+    /// blocking the calling thread.
+    pub trait AsyncBufRead: AsyncRead {
+        /// Attempt to return the contents of the internal buffer, filling it
+        /// with more data from the inner reader if it is empty.
+        ///
+        /// On success, returns `Ok(Async::Ready(buf))`.
+        ///
+        /// If no data is available for reading, the method returns
+        /// `Ok(Async::Pending)` and arranges for the current task (via
+        /// `lw.waker()`) to receive a notification when the object becomes
+        /// readable or is closed.
+        ///
+        /// This function is a lower-level call. It needs to be paired with the
+        /// [`consume`](AsyncBufRead::consume) method to function properly. When
+        /// calling this method, none of the contents will be "read" in the
+        /// sense that later calling `poll_read` may return the same contents.
+        /// As such, `consume` must be called with the number of bytes that are
+        /// consumed from this buffer to ensure that the bytes are never
+        /// returned twice.
+        ///
+        /// An empty buffer returned indicates that the stream has reached EOF.
+        ///
+        /// # Implementation
+        ///
+        /// This function may not return errors of kind `WouldBlock` or
+        /// `Interrupted`.  Implementations must convert `WouldBlock` into
+        /// `Async::Pending` and either internally retry or convert
+        /// `Interrupted` into another error kind.
+        fn poll_fill_buf(&mut self, lw: &LocalWaker)
+            -> Poll<Result<&[u8]>>;
+
+        /// Tells this buffer that `amt` bytes have been consumed from the
+        /// buffer, so they should no longer be returned in calls to
+        /// `poll_read`.
+        ///
+        /// This function is a lower-level call. It needs to be paired with the
+        /// [`poll_fill_buf`](AsyncBufRead::poll_fill_buf) method to function
+        /// properly. This function does not perform any I/O, it simply informs
+        /// this object that some amount of its buffer, returned from
+        /// `poll_fill_buf`, has been consumed and should no longer be returned.
+        /// As such, this function may do odd things if `poll_fill_buf` isn't
+        /// called before calling it.
+        ///
+        /// The `amt` must be `<=` the number of bytes in the buffer returned by
+        /// `poll_fill_buf`.
+        fn consume(&mut self, amt: usize);
+    }
+
     macro_rules! deref_async_read {
         () => {
             unsafe fn initializer(&self) -> Initializer {
@@ -297,6 +384,76 @@ mod if_std {
         unsafe_delegate_async_read_to_stdio!();
     }
 
+    macro_rules! deref_async_seek {
+        () => {
+            fn poll_seek(&mut self, lw: &LocalWaker, pos: SeekFrom)
+                -> Poll<Result<u64>>
+            {
+                (**self).poll_seek(lw, pos)
+            }
+        }
+    }
+
+    impl<T: ?Sized + AsyncSeek> AsyncSeek for Box<T> {
+        deref_async_seek!();
+    }
+
+    impl<'a, T: ?Sized + AsyncSeek> AsyncSeek for &'a mut T {
+        deref_async_seek!();
+    }
+
+    impl<T: AsRef<[u8]>> AsyncSeek for StdIo::Cursor<T> {
+        fn poll_seek(&mut self, _: &LocalWaker, pos: SeekFrom)
+            -> Poll<Result<u64>>
+        {
+            Poll::Ready(StdIo::Seek::seek(self, pos))
+        }
+    }
+
+    macro_rules! deref_async_buf_read {
+        () => {
+            fn poll_fill_buf(&mut self, lw: &LocalWaker)
+                -> Poll<Result<&[u8]>>
+            {
+                (**self).poll_fill_buf(lw)
+            }
+
+            fn consume(&mut self, amt: usize) {
+                (**self).consume(amt)
+            }
+        }
+    }
+
+    impl<T: ?Sized + AsyncBufRead> AsyncBufRead for Box<T> {
+        deref_async_buf_read!();
+    }
+
+    impl<'a, T: ?Sized + AsyncBufRead> AsyncBufRead for &'a mut T {
+        deref_async_buf_read!();
+    }
+
+    macro_rules! delegate_async_buf_read_to_stdio {
+        () => {
+            fn poll_fill_buf(&mut self, _: &LocalWaker)
+                -> Poll<Result<&[u8]>>
+            {
+                Poll::Ready(StdIo::BufRead::fill_buf(self))
+            }
+
+            fn consume(&mut self, amt: usize) {
+                StdIo::BufRead::consume(self, amt)
+            }
+        }
+    }
+
+    impl<'a> AsyncBufRead for &'a [u8] {
+        delegate_async_buf_read_to_stdio!();
+    }
+
+    impl<T: AsRef<[u8]>> AsyncBufRead for StdIo::Cursor<T> {
+        delegate_async_buf_read_to_stdio!();
+    }
+
     macro_rules! deref_async_write {
         () => {
             fn poll_write(&mut self, lw: &LocalWaker, buf: &[u8])
@@ -3,7 +3,7 @@
 use assert_matches::assert_matches;
 use futures::Poll;
 use futures::future::lazy;
-use futures::io::AsyncWrite;
+use futures::io::{AsyncSeek, AsyncWrite, SeekFrom};
 use std::io::Cursor;
 
 #[test]
@@ -17,3 +17,15 @@ fn cursor_asyncwrite_asmut() {
     }));
     assert_eq!(cursor.into_inner(), [1, 2, 3, 4, 5]);
 }
+
+#[test]
+fn cursor_asyncseek() {
+    let mut cursor = Cursor::new([0; 5]);
+    futures::executor::block_on(lazy(|ctx| {
+        assert_matches!(cursor.poll_seek(ctx, SeekFrom::Start(2)), Poll::Ready(Ok(2)));
+        assert_matches!(cursor.poll_seek(ctx, SeekFrom::Current(-1)), Poll::Ready(Ok(1)));
+        assert_matches!(cursor.poll_seek(ctx, SeekFrom::End(0)), Poll::Ready(Ok(5)));
+        assert_matches!(cursor.poll_seek(ctx, SeekFrom::Current(-6)), Poll::Ready(Err(_)));
+    }));
+    assert_eq!(cursor.position(), 5);
+}
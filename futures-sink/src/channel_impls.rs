@@ -1,6 +1,7 @@
 use crate::{Sink, Poll};
 use futures_core::task::LocalWaker;
-use futures_channel::mpsc::{Sender, SendError, UnboundedSender};
+use futures_channel::{broadcast, mpmc};
+use futures_channel::mpsc::{PrioritySender, Sender, SendError, UnboundedSender};
 use std::pin::Pin;
 
 impl<T> Sink for Sender<T> {
@@ -69,3 +70,71 @@ impl<'a, T> Sink for &'a UnboundedSender<T> {
         Poll::Ready(Ok(()))
     }
 }
+
+impl<P: Ord, T> Sink for PrioritySender<P, T> {
+    type SinkItem = (P, T);
+    type SinkError = SendError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        (*self).poll_ready(lw)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, (priority, msg): (P, T)) -> Result<(), Self::SinkError> {
+        (*self).start_send(priority, msg)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        self.disconnect();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Sink for mpmc::Sender<T> {
+    type SinkItem = T;
+    type SinkError = SendError;
+
+    fn poll_ready(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        (*self).poll_ready(lw)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, msg: T) -> Result<(), Self::SinkError> {
+        (*self).start_send(msg)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        self.disconnect();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<T> Sink for broadcast::Sender<T> {
+    type SinkItem = T;
+    type SinkError = broadcast::SendError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        // Broadcasting overwrites the oldest message rather than waiting for
+        // capacity, so the sender is always ready.
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, msg: T) -> Result<(), Self::SinkError> {
+        self.send(msg)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        self.disconnect();
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,9 @@
+//! Executors for testing futures.
+//!
+//! - [`TestExecutor`] runs tasks on a single thread against a virtual clock,
+//!   in an order determined by a seed. Running the same test with the same
+//!   seed interleaves its tasks the same way every time, which makes
+//!   concurrency bugs reproducible.
+
+mod test_executor;
+pub use self::test_executor::{TestExecutor, TestSpawner};
@@ -0,0 +1,427 @@
+use futures_core::future::{Future, FutureObj, LocalFutureObj};
+use futures_core::task::{LocalSpawn, Poll, Spawn, SpawnError, Wake};
+use futures_executor::enter;
+use futures_executor::timer::{MockClock, Timer};
+use futures_util::task::local_waker_ref_from_nonlocal;
+use pin_utils::pin_mut;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+/// A single-threaded executor with a virtual clock and a seeded scheduling
+/// order, for testing.
+///
+/// Whenever more than one task is ready, the executor picks the next one to
+/// poll at random, using a generator seeded with the value passed to
+/// [`with_seed`](TestExecutor::with_seed). The order in which the tasks run
+/// therefore only depends on the seed, so a failure found with one seed can
+/// be reproduced by running the test again with that seed.
+///
+/// The executor owns a [mock timer](futures_executor::timer::Timer::mock),
+/// available from [`timer`](TestExecutor::timer). Once no task is ready, the
+/// virtual clock is advanced straight to the next pending deadline of that
+/// timer, so tests involving long delays complete right away.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(async_await, await_macro, futures_api)]
+/// use futures::task::SpawnExt;
+/// use futures_test::executor::TestExecutor;
+/// use std::time::Duration;
+///
+/// let mut executor = TestExecutor::with_seed(7);
+/// let timer = executor.timer().clone();
+/// let start = executor.now();
+///
+/// executor.spawner().spawn(async move {
+///     await!(timer.delay(Duration::from_secs(3600)));
+/// }).unwrap();
+///
+/// // Returns right away, with an hour passed on the virtual clock.
+/// executor.run();
+/// assert_eq!(executor.now() - start, Duration::from_secs(3600));
+/// ```
+pub struct TestExecutor {
+    tasks: HashMap<usize, Task>,
+    next_id: usize,
+    incoming: Rc<Incoming>,
+    ready: Arc<ReadyQueue>,
+    timer: Timer,
+    clock: MockClock,
+    seed: u64,
+    rng: Rng,
+}
+
+/// A handle to a [`TestExecutor`](TestExecutor) that implements
+/// [`Spawn`](futures_core::task::Spawn) and
+/// [`LocalSpawn`](futures_core::task::LocalSpawn).
+#[derive(Clone, Debug)]
+pub struct TestSpawner {
+    incoming: Weak<Incoming>,
+}
+
+type Incoming = RefCell<Vec<LocalFutureObj<'static, ()>>>;
+
+struct Task {
+    future: LocalFutureObj<'static, ()>,
+    waker: Arc<TaskWaker>,
+}
+
+// The ids of the woken tasks, shared between the executor and the wakers of
+// its tasks.
+struct ReadyQueue {
+    ids: Mutex<Vec<usize>>,
+    // The thread running the executor, woken when a task is woken from
+    // another thread.
+    thread: Thread,
+}
+
+struct TaskWaker {
+    id: usize,
+    // Set while the task is in the ready queue.
+    queued: AtomicBool,
+    ready: Arc<ReadyQueue>,
+}
+
+// The id of the future passed to `run_until`, which is scheduled along with
+// the spawned tasks.
+const MAIN: usize = usize::max_value();
+
+impl TaskWaker {
+    fn new(id: usize, ready: Arc<ReadyQueue>) -> TaskWaker {
+        TaskWaker {
+            id,
+            queued: AtomicBool::new(false),
+            ready,
+        }
+    }
+}
+
+impl Wake for TaskWaker {
+    fn wake(arc_self: &Arc<Self>) {
+        if !arc_self.queued.swap(true, Ordering::SeqCst) {
+            arc_self.ready.ids.lock().unwrap().push(arc_self.id);
+            arc_self.ready.thread.unpark();
+        }
+    }
+}
+
+impl TestExecutor {
+    /// Creates a new executor, using a seed of `0` for the scheduling order.
+    pub fn new() -> TestExecutor {
+        TestExecutor::with_seed(0)
+    }
+
+    /// Creates a new executor whose scheduling order is determined by `seed`.
+    pub fn with_seed(seed: u64) -> TestExecutor {
+        let (timer, clock) = Timer::mock();
+        TestExecutor {
+            tasks: HashMap::new(),
+            next_id: 0,
+            incoming: Default::default(),
+            ready: Arc::new(ReadyQueue {
+                ids: Mutex::new(Vec::new()),
+                thread: thread::current(),
+            }),
+            timer,
+            clock,
+            seed,
+            rng: Rng(seed),
+        }
+    }
+
+    /// The seed this executor was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get a clonable handle to the executor as a [`Spawn`].
+    pub fn spawner(&self) -> TestSpawner {
+        TestSpawner {
+            incoming: Rc::downgrade(&self.incoming),
+        }
+    }
+
+    /// The timer following the virtual clock of this executor.
+    ///
+    /// Delays, intervals and timeouts created from this timer are the ones
+    /// the executor skips ahead to when it is idle.
+    pub fn timer(&self) -> &Timer {
+        &self.timer
+    }
+
+    /// Returns the current time on the virtual clock of this executor.
+    pub fn now(&self) -> Instant {
+        self.clock.now()
+    }
+
+    /// Runs all tasks to completion, including any spawned while running.
+    ///
+    /// If tasks remain which are neither ready nor waiting on the timer, the
+    /// calling thread is blocked until one of them is woken from another
+    /// thread.
+    pub fn run(&mut self) {
+        let _enter = enter()
+            .expect("cannot execute `TestExecutor` from within another executor");
+
+        loop {
+            self.spawn_incoming();
+            if self.tasks.is_empty() {
+                return
+            }
+            match self.next_ready() {
+                Some(id) => self.poll_task(id),
+                None => self.idle(),
+            }
+        }
+    }
+
+    /// Runs the tasks until the given future completes, returning its
+    /// output.
+    ///
+    /// The future is polled as if it were one of the tasks, so its place in
+    /// the scheduling order is determined by the seed as well.
+    pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        let _enter = enter()
+            .expect("cannot execute `TestExecutor` from within another executor");
+
+        pin_mut!(future);
+        let main = Arc::new(TaskWaker::new(MAIN, self.ready.clone()));
+        Wake::wake(&main);
+
+        loop {
+            self.spawn_incoming();
+            match self.next_ready() {
+                Some(MAIN) => {
+                    main.queued.store(false, Ordering::SeqCst);
+                    let lw = local_waker_ref_from_nonlocal(&main);
+                    if let Poll::Ready(output) = future.as_mut().poll(&lw) {
+                        return output
+                    }
+                }
+                Some(id) => self.poll_task(id),
+                None => self.idle(),
+            }
+        }
+    }
+
+    /// Runs the tasks until none of them is ready, without advancing the
+    /// virtual clock.
+    pub fn run_until_stalled(&mut self) {
+        let _enter = enter()
+            .expect("cannot execute `TestExecutor` from within another executor");
+
+        loop {
+            self.spawn_incoming();
+            match self.next_ready() {
+                Some(id) => self.poll_task(id),
+                None => return,
+            }
+        }
+    }
+
+    // Moves newly spawned tasks into the executor, marking them as ready.
+    fn spawn_incoming(&mut self) {
+        let incoming = mem::replace(&mut *self.incoming.borrow_mut(), Vec::new());
+        for future in incoming {
+            let id = self.next_id;
+            self.next_id += 1;
+            let waker = Arc::new(TaskWaker::new(id, self.ready.clone()));
+            Wake::wake(&waker);
+            self.tasks.insert(id, Task { future, waker });
+        }
+    }
+
+    // Takes one of the ready tasks at random.
+    fn next_ready(&mut self) -> Option<usize> {
+        let mut ids = self.ready.ids.lock().unwrap();
+        if ids.is_empty() {
+            return None
+        }
+        let idx = (self.rng.next() % ids.len() as u64) as usize;
+        Some(ids.swap_remove(idx))
+    }
+
+    fn poll_task(&mut self, id: usize) {
+        let done = match self.tasks.get_mut(&id) {
+            Some(task) => {
+                // Wakeups from here on queue the task up again.
+                task.waker.queued.store(false, Ordering::SeqCst);
+                let lw = local_waker_ref_from_nonlocal(&task.waker);
+                Pin::new(&mut task.future).poll(&lw).is_ready()
+            }
+            // A stale wakeup for a finished task, or for the future of an
+            // earlier `run_until`.
+            None => return,
+        };
+        if done {
+            self.tasks.remove(&id);
+        }
+    }
+
+    // Called when no task is ready. Advances the virtual clock to the next
+    // pending deadline, or waits for a wakeup from another thread if there
+    // is none.
+    fn idle(&mut self) {
+        match self.clock.next_deadline() {
+            Some(deadline) => {
+                let now = self.clock.now();
+                let dur = if deadline > now {
+                    deadline - now
+                } else {
+                    Duration::from_secs(0)
+                };
+                self.clock.advance(dur);
+            }
+            None => thread::park(),
+        }
+    }
+}
+
+impl Default for TestExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for TestExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestExecutor")
+            .field("seed", &self.seed)
+            .field("tasks", &self.tasks.len())
+            .field("now", &self.now())
+            .finish()
+    }
+}
+
+impl Spawn for TestSpawner {
+    fn spawn_obj(
+        &mut self,
+        future: FutureObj<'static, ()>,
+    ) -> Result<(), SpawnError> {
+        self.spawn_local_obj(future.into())
+    }
+
+    fn status(&self) -> Result<(), SpawnError> {
+        self.status_local()
+    }
+}
+
+impl LocalSpawn for TestSpawner {
+    fn spawn_local_obj(
+        &mut self,
+        future: LocalFutureObj<'static, ()>,
+    ) -> Result<(), SpawnError> {
+        if let Some(incoming) = self.incoming.upgrade() {
+            incoming.borrow_mut().push(future);
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+
+    fn status_local(&self) -> Result<(), SpawnError> {
+        if self.incoming.upgrade().is_some() {
+            Ok(())
+        } else {
+            Err(SpawnError::shutdown())
+        }
+    }
+}
+
+// SplitMix64. Scheduling only needs the sequence to be reproducible, not to
+// be of high quality.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::future::{self, FutureExt};
+    use futures_util::task::LocalSpawnExt;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    use super::TestExecutor;
+
+    fn run_order(seed: u64) -> Vec<usize> {
+        let mut executor = TestExecutor::with_seed(seed);
+        let mut spawner = executor.spawner();
+        let order = Rc::new(RefCell::new(Vec::new()));
+        for i in 0..16 {
+            let order = order.clone();
+            spawner.spawn_local(future::lazy(move |_| {
+                order.borrow_mut().push(i);
+            })).unwrap();
+        }
+        executor.run();
+        let order = order.borrow().clone();
+        order
+    }
+
+    #[test]
+    fn same_seed_same_order() {
+        for seed in 0..8 {
+            assert_eq!(run_order(seed), run_order(seed));
+        }
+    }
+
+    #[test]
+    fn seed_changes_order() {
+        let orders = (0..8).map(run_order).collect::<Vec<_>>();
+        assert!(orders.iter().any(|order| *order != orders[0]));
+    }
+
+    #[test]
+    fn advances_to_next_deadline() {
+        let mut executor = TestExecutor::new();
+        let start = executor.now();
+        let timer = executor.timer().clone();
+        let fired = Rc::new(RefCell::new(Vec::new()));
+
+        for &secs in &[30, 10, 20] {
+            let fired = fired.clone();
+            let timer = timer.clone();
+            executor.spawner().spawn_local(timer.delay(Duration::from_secs(secs)).map(move |()| {
+                fired.borrow_mut().push(timer.now() - start);
+            })).unwrap();
+        }
+
+        // Nothing is ready without time passing.
+        executor.run_until_stalled();
+        assert!(fired.borrow().is_empty());
+        assert_eq!(executor.now(), start);
+
+        executor.run();
+        assert_eq!(*fired.borrow(), vec![
+            Duration::from_secs(10),
+            Duration::from_secs(20),
+            Duration::from_secs(30),
+        ]);
+    }
+
+    #[test]
+    fn run_until_output() {
+        let mut executor = TestExecutor::new();
+        let delay = executor.timer().delay(Duration::from_secs(5));
+        assert_eq!(executor.run_until(delay.map(|()| 3)), 3);
+        assert_eq!(executor.run_until(future::ready(4)), 4);
+    }
+}
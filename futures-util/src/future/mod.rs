@@ -31,6 +31,9 @@ pub use self::poll_fn::{poll_fn, PollFn};
 mod ready;
 pub use self::ready::{ready, ok, err, Ready};
 
+mod yield_now;
+pub use self::yield_now::{yield_now, YieldNow};
+
 // Combinators
 mod flatten;
 pub use self::flatten::Flatten;
@@ -0,0 +1,60 @@
+use core::pin::Pin;
+use futures_core::future::{Future, FusedFuture};
+use futures_core::task::{LocalWaker, Poll};
+
+/// A future which yields control back to the executor once before
+/// completing.
+///
+/// This future can be created with the [`yield_now()`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct YieldNow {
+    yielded: bool,
+    done: bool,
+}
+
+impl FusedFuture for YieldNow {
+    fn is_terminated(&self) -> bool { self.done }
+}
+
+/// Creates a future which yields control back to the executor once before
+/// completing.
+///
+/// The first time the returned future is polled it wakes the current task
+/// and returns [`Poll::Pending`], completing on the next poll. This allows a
+/// long-running task to give other tasks on the same executor a chance to
+/// run. How soon the task is polled again depends on the executor;
+/// [`LocalPool`](../executor/struct.LocalPool.html) polls all other ready
+/// tasks first.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(async_await, await_macro, futures_api)]
+/// # futures::executor::block_on(async {
+/// use futures::future;
+///
+/// for _ in 0..3 {
+///     // ... do some work ...
+///     await!(future::yield_now());
+/// }
+/// # });
+/// ```
+pub fn yield_now() -> YieldNow {
+    YieldNow { yielded: false, done: false }
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<()> {
+        if self.yielded {
+            self.done = true;
+            Poll::Ready(())
+        } else {
+            self.yielded = true;
+            lw.wake();
+            Poll::Pending
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use futures_core::task::{LocalWaker, Poll};
-use futures_io::{AsyncRead, AsyncWrite};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite};
 use std::{fmt, io};
 use std::string::String;
 use std::vec::Vec;
@@ -113,3 +113,42 @@ impl<T> AsyncRead for AllowStdIo<T> where T: io::Read {
         Poll::Ready(Ok(try_with_interrupt!(io::Read::read(&mut self.0, buf))))
     }
 }
+
+impl<T> io::Seek for AllowStdIo<T> where T: io::Seek {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+impl<T> AsyncSeek for AllowStdIo<T> where T: io::Seek {
+    fn poll_seek(&mut self, _: &LocalWaker, pos: io::SeekFrom)
+        -> Poll<io::Result<u64>>
+    {
+        Poll::Ready(Ok(try_with_interrupt!(io::Seek::seek(&mut self.0, pos))))
+    }
+}
+
+impl<T> io::BufRead for AllowStdIo<T> where T: io::BufRead {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.0.fill_buf()
+    }
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
+
+impl<T> AsyncBufRead for AllowStdIo<T> where T: io::BufRead {
+    fn poll_fill_buf(&mut self, _: &LocalWaker)
+        -> Poll<io::Result<&[u8]>>
+    {
+        // The borrow checker does not accept returning the buffer from inside
+        // the retry loop, as the borrow of one iteration would overlap the
+        // next. Only the final, successful borrow escapes, so this is safe.
+        let this: *mut Self = self as *mut _;
+        Poll::Ready(Ok(try_with_interrupt!(unsafe { &mut *this }.0.fill_buf())))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.0.consume(amt)
+    }
+}
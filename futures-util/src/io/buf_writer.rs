@@ -0,0 +1,233 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::{AsyncSeek, AsyncWrite, SeekFrom};
+use std::error;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::vec::Vec;
+use super::DEFAULT_BUF_SIZE;
+
+/// Wraps a writer and buffers its output.
+///
+/// It can be excessively inefficient to work directly with something that
+/// implements [`AsyncWrite`]. A `BufWriter` keeps an in-memory buffer of data
+/// and writes it to an underlying writer in large, infrequent batches.
+///
+/// `BufWriter` can improve the speed of programs that make *small* and
+/// *repeated* write calls to the same stream or socket. It does not help when
+/// writing very large amounts at once, or writing just one or a few times. It
+/// also provides no advantage when writing to a destination that is in
+/// memory, like a `Vec<u8>`.
+///
+/// Unlike `std::io::BufWriter`, the buffer is *not* written out when the
+/// `BufWriter` is dropped, as that would require blocking. Use
+/// [`flush`](crate::io::AsyncWriteExt::flush),
+/// [`close`](crate::io::AsyncWriteExt::close) or
+/// [`into_inner`](BufWriter::into_inner) to make sure the buffered data
+/// reaches the underlying writer.
+///
+/// [`AsyncWrite`]: futures_io::AsyncWrite
+pub struct BufWriter<W> {
+    inner: W,
+    buf: Vec<u8>,
+    written: usize,
+}
+
+// Pinning is never projected to fields
+impl<W> Unpin for BufWriter<W> {}
+
+impl<W: AsyncWrite> BufWriter<W> {
+    /// Creates a new `BufWriter` with a default buffer capacity. The default is currently 8 KB,
+    /// but may change in the future.
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    /// Creates a new `BufWriter` with the specified buffer capacity.
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        BufWriter {
+            inner,
+            buf: Vec::with_capacity(capacity),
+            written: 0,
+        }
+    }
+
+    /// Unwraps this `BufWriter`, returning the underlying writer.
+    ///
+    /// The returned future flushes the buffer before resolving to the
+    /// underlying writer. If flushing fails, it resolves to an
+    /// [`IntoInnerError`] holding both the error and this `BufWriter`, whose
+    /// [`buffer`](BufWriter::buffer) still contains the data that could not
+    /// be written.
+    pub fn into_inner(self) -> IntoInner<W> {
+        IntoInner { writer: Some(self) }
+    }
+
+    fn flush_buf(&mut self, lw: &LocalWaker) -> Poll<io::Result<()>> {
+        let len = self.buf.len();
+        let mut ret = Ok(());
+        while self.written < len {
+            match self.inner.poll_write(lw, &self.buf[self.written..]) {
+                Poll::Ready(Ok(0)) => {
+                    ret = Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    ));
+                    break;
+                }
+                Poll::Ready(Ok(n)) => self.written += n,
+                Poll::Ready(Err(e)) => {
+                    ret = Err(e);
+                    break;
+                }
+                // `written` is kept, so the next call resumes where this one
+                // left off.
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        if self.written > 0 {
+            self.buf.drain(..self.written);
+        }
+        self.written = 0;
+        Poll::Ready(ret)
+    }
+}
+
+impl<W> BufWriter<W> {
+    /// Gets a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// It is inadvisable to directly write to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the internally buffered data.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Returns the number of bytes the internal buffer can hold without
+    /// flushing.
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+}
+
+impl<W: AsyncWrite> AsyncWrite for BufWriter<W> {
+    fn poll_write(&mut self, lw: &LocalWaker, buf: &[u8])
+        -> Poll<io::Result<usize>>
+    {
+        if self.buf.len() + buf.len() > self.buf.capacity() {
+            try_ready!(self.flush_buf(lw));
+        }
+        if buf.len() >= self.buf.capacity() {
+            self.inner.poll_write(lw, buf)
+        } else {
+            self.buf.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+    }
+
+    fn poll_flush(&mut self, lw: &LocalWaker) -> Poll<io::Result<()>> {
+        try_ready!(self.flush_buf(lw));
+        self.inner.poll_flush(lw)
+    }
+
+    fn poll_close(&mut self, lw: &LocalWaker) -> Poll<io::Result<()>> {
+        try_ready!(self.flush_buf(lw));
+        self.inner.poll_close(lw)
+    }
+}
+
+impl<W: AsyncWrite + AsyncSeek> AsyncSeek for BufWriter<W> {
+    /// Seek to the offset, in bytes, in the underlying writer.
+    ///
+    /// Seeking always writes out the internal buffer before seeking.
+    fn poll_seek(&mut self, lw: &LocalWaker, pos: SeekFrom)
+        -> Poll<io::Result<u64>>
+    {
+        try_ready!(self.flush_buf(lw));
+        self.inner.poll_seek(lw, pos)
+    }
+}
+
+impl<W: fmt::Debug> fmt::Debug for BufWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufWriter")
+            .field("writer", &self.inner)
+            .field("buffer", &format_args!("{}/{}", self.buf.len(), self.buf.capacity()))
+            .field("written", &self.written)
+            .finish()
+    }
+}
+
+/// Future for the [`into_inner`](BufWriter::into_inner) method.
+#[derive(Debug)]
+pub struct IntoInner<W> {
+    writer: Option<BufWriter<W>>,
+}
+
+// Pinning is never projected to fields
+impl<W> Unpin for IntoInner<W> {}
+
+impl<W: AsyncWrite> Future for IntoInner<W> {
+    type Output = Result<W, IntoInnerError<BufWriter<W>>>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let res = ready!(self.writer.as_mut()
+            .expect("polled IntoInner after completion")
+            .flush_buf(lw));
+        let writer = self.writer.take().unwrap();
+        Poll::Ready(match res {
+            Ok(()) => Ok(writer.inner),
+            Err(e) => Err(IntoInnerError(writer, e)),
+        })
+    }
+}
+
+/// An error returned by [`into_inner`](BufWriter::into_inner) which combines
+/// an error that happened while writing out the buffer, and the buffered
+/// writer object which may be used to recover from the condition.
+#[derive(Debug)]
+pub struct IntoInnerError<W>(W, io::Error);
+
+impl<W> IntoInnerError<W> {
+    /// Returns the error which caused the call to `into_inner()` to fail.
+    ///
+    /// This error was returned when attempting to write the internal buffer.
+    pub fn error(&self) -> &io::Error {
+        &self.1
+    }
+
+    /// Returns the buffered writer instance which generated the error.
+    ///
+    /// The returned object can be used for error recovery, such as
+    /// re-inspecting the buffer.
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+impl<W> From<IntoInnerError<W>> for io::Error {
+    fn from(iie: IntoInnerError<W>) -> io::Error {
+        iie.1
+    }
+}
+
+impl<W: Send + fmt::Debug> error::Error for IntoInnerError<W> {
+    fn description(&self) -> &str {
+        error::Error::description(self.error())
+    }
+}
+
+impl<W> fmt::Display for IntoInnerError<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.error(), f)
+    }
+}
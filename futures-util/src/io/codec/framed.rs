@@ -0,0 +1,128 @@
+use super::framed_read::ReadFrame;
+use super::framed_write::WriteFrame;
+use super::{Decoder, Encoder};
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+use std::pin::Pin;
+
+/// A unified `Stream` and `Sink` interface to an underlying I/O object, using
+/// a codec which implements both [`Decoder`] and [`Encoder`] to read and
+/// write frames.
+///
+/// Use [`StreamExt::split`](crate::stream::StreamExt::split) to get separate
+/// handles to the stream and sink halves.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Framed<T, U> {
+    inner: T,
+    codec: U,
+    read: ReadFrame,
+    write: WriteFrame,
+}
+
+// Pinning is never projected to fields
+impl<T, U> Unpin for Framed<T, U> {}
+
+impl<T, U> Framed<T, U>
+    where T: AsyncRead + AsyncWrite,
+          U: Decoder + Encoder,
+{
+    /// Creates a new `Framed` reading and writing frames of `inner` with
+    /// `codec`.
+    pub fn new(inner: T, codec: U) -> Self {
+        Framed {
+            inner,
+            codec,
+            read: ReadFrame::default(),
+            write: WriteFrame::default(),
+        }
+    }
+}
+
+impl<T, U> Framed<T, U> {
+    /// Returns a reference to the underlying I/O object.
+    ///
+    /// Note that care should be taken to not tamper with the underlying I/O
+    /// object as it may corrupt the frames otherwise being worked with.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying I/O object.
+    ///
+    /// Note that care should be taken to not tamper with the underlying I/O
+    /// object as it may corrupt the frames otherwise being worked with.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Consumes the `Framed`, returning its underlying I/O object.
+    ///
+    /// Note that any bytes read but not yet decoded, and any encoded frames
+    /// that have not yet been flushed, are lost.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns a reference to the codec.
+    pub fn codec(&self) -> &U {
+        &self.codec
+    }
+
+    /// Returns a mutable reference to the codec.
+    pub fn codec_mut(&mut self) -> &mut U {
+        &mut self.codec
+    }
+
+    /// Returns the bytes read but not yet decoded.
+    pub fn read_buffer(&self) -> &[u8] {
+        self.read.buffer()
+    }
+
+    /// Returns the encoded bytes that have not yet been written out.
+    pub fn write_buffer(&self) -> &[u8] {
+        self.write.buffer()
+    }
+}
+
+impl<T: AsyncRead, U: Decoder> FusedStream for Framed<T, U> {
+    fn is_terminated(&self) -> bool {
+        self.read.is_terminated()
+    }
+}
+
+impl<T: AsyncRead, U: Decoder> Stream for Framed<T, U> {
+    type Item = Result<U::Item, U::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        this.read.poll_next(lw, &mut this.inner, &mut this.codec)
+    }
+}
+
+impl<T: AsyncWrite, U: Encoder> Sink for Framed<T, U> {
+    type SinkItem = U::Item;
+    type SinkError = U::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        let this = &mut *self;
+        this.write.poll_ready(lw, &mut this.inner)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Self::SinkItem) -> Result<(), Self::SinkError> {
+        let this = &mut *self;
+        this.write.start_send(&mut this.codec, item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        let this = &mut *self;
+        this.write.poll_flush(lw, &mut this.inner)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        let this = &mut *self;
+        this.write.poll_close(lw, &mut this.inner)
+    }
+}
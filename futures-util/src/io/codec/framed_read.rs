@@ -0,0 +1,184 @@
+use super::{Decoder, INITIAL_CAPACITY};
+use futures_core::stream::{FusedStream, Stream};
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::AsyncRead;
+use std::pin::Pin;
+use std::vec::Vec;
+
+/// A `Stream` of frames decoded from an `AsyncRead`.
+///
+/// Each item is produced by calling the [`Decoder`] on the bytes read so far.
+/// The stream ends once the underlying reader reaches EOF and
+/// [`decode_eof`](Decoder::decode_eof) returns `Ok(None)`.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct FramedRead<R, D> {
+    inner: R,
+    decoder: D,
+    state: ReadFrame,
+}
+
+// Pinning is never projected to fields
+impl<R, D> Unpin for FramedRead<R, D> {}
+
+#[derive(Debug)]
+pub(super) struct ReadFrame {
+    buffer: Vec<u8>,
+    eof: bool,
+    is_readable: bool,
+    done: bool,
+}
+
+impl Default for ReadFrame {
+    fn default() -> Self {
+        ReadFrame {
+            buffer: Vec::with_capacity(INITIAL_CAPACITY),
+            eof: false,
+            is_readable: false,
+            done: false,
+        }
+    }
+}
+
+impl ReadFrame {
+    pub(super) fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    pub(super) fn is_terminated(&self) -> bool {
+        self.done
+    }
+
+    pub(super) fn poll_next<R, D>(
+        &mut self,
+        lw: &LocalWaker,
+        reader: &mut R,
+        decoder: &mut D,
+    ) -> Poll<Option<Result<D::Item, D::Error>>>
+        where R: AsyncRead + ?Sized,
+              D: Decoder,
+    {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        loop {
+            // Repeatedly call `decode` or `decode_eof` as long as it is
+            // "readable". Readable is defined as not having returned `None`.
+            // If the underlying reader has reached EOF, `decode_eof` is called
+            // until it returns `None`, which ends the stream.
+            if self.is_readable {
+                if self.eof {
+                    let frame = match decoder.decode_eof(&mut self.buffer) {
+                        Ok(frame) => frame,
+                        Err(e) => {
+                            self.done = true;
+                            return Poll::Ready(Some(Err(e)));
+                        }
+                    };
+                    if frame.is_none() {
+                        self.done = true;
+                    }
+                    return Poll::Ready(frame.map(Ok));
+                }
+
+                match decoder.decode(&mut self.buffer) {
+                    Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame))),
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(e)));
+                    }
+                }
+
+                self.is_readable = false;
+            }
+
+            // Otherwise, try to read more data and try again.
+            let len = self.buffer.len();
+            self.buffer.resize(len + INITIAL_CAPACITY, 0);
+            let res = reader.poll_read(lw, &mut self.buffer[len..]);
+            let n = match res {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => {
+                    self.buffer.truncate(len);
+                    self.done = true;
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+                Poll::Pending => {
+                    self.buffer.truncate(len);
+                    return Poll::Pending;
+                }
+            };
+            self.buffer.truncate(len + n);
+            if n == 0 {
+                self.eof = true;
+            }
+            self.is_readable = true;
+        }
+    }
+}
+
+impl<R: AsyncRead, D: Decoder> FramedRead<R, D> {
+    /// Creates a new `FramedRead` decoding the bytes of `inner` with
+    /// `decoder`.
+    pub fn new(inner: R, decoder: D) -> Self {
+        FramedRead { inner, decoder, state: ReadFrame::default() }
+    }
+}
+
+impl<R, D> FramedRead<R, D> {
+    /// Returns a reference to the underlying reader.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// reader as it may corrupt the stream of frames otherwise being worked
+    /// with.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// reader as it may corrupt the stream of frames otherwise being worked
+    /// with.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Consumes the `FramedRead`, returning its underlying reader.
+    ///
+    /// Note that any bytes read but not yet decoded are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Returns a reference to the decoder.
+    pub fn decoder(&self) -> &D {
+        &self.decoder
+    }
+
+    /// Returns a mutable reference to the decoder.
+    pub fn decoder_mut(&mut self) -> &mut D {
+        &mut self.decoder
+    }
+
+    /// Returns the bytes read but not yet decoded.
+    pub fn read_buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<R: AsyncRead, D: Decoder> FusedStream for FramedRead<R, D> {
+    fn is_terminated(&self) -> bool {
+        self.state.is_terminated()
+    }
+}
+
+impl<R: AsyncRead, D: Decoder> Stream for FramedRead<R, D> {
+    type Item = Result<D::Item, D::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        this.state.poll_next(lw, &mut this.inner, &mut this.decoder)
+    }
+}
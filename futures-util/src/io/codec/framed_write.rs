@@ -0,0 +1,198 @@
+use super::{Encoder, BACKPRESSURE_BOUNDARY, INITIAL_CAPACITY};
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::AsyncWrite;
+use futures_sink::Sink;
+use std::io;
+use std::pin::Pin;
+use std::vec::Vec;
+
+/// A `Sink` of frames encoded to an `AsyncWrite`.
+///
+/// Each item passed to `start_send` is encoded with the [`Encoder`] into an
+/// internal buffer, which is written out to the underlying writer when the
+/// sink is flushed or closed, or when the buffer grows too large.
+#[derive(Debug)]
+#[must_use = "sinks do nothing unless polled"]
+pub struct FramedWrite<W, E> {
+    inner: W,
+    encoder: E,
+    state: WriteFrame,
+}
+
+// Pinning is never projected to fields
+impl<W, E> Unpin for FramedWrite<W, E> {}
+
+#[derive(Debug)]
+pub(super) struct WriteFrame {
+    buffer: Vec<u8>,
+}
+
+impl Default for WriteFrame {
+    fn default() -> Self {
+        WriteFrame { buffer: Vec::with_capacity(INITIAL_CAPACITY) }
+    }
+}
+
+impl WriteFrame {
+    pub(super) fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    pub(super) fn poll_ready<W, E>(
+        &mut self,
+        lw: &LocalWaker,
+        writer: &mut W,
+    ) -> Poll<Result<(), E>>
+        where W: AsyncWrite + ?Sized,
+              E: From<io::Error>,
+    {
+        // If the buffer is already over the boundary, try to write some of
+        // it out before accepting another frame.
+        if self.buffer.len() >= BACKPRESSURE_BOUNDARY {
+            try_ready!(self.poll_flush_buffer(lw, writer));
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    pub(super) fn start_send<E: Encoder>(
+        &mut self,
+        encoder: &mut E,
+        item: E::Item,
+    ) -> Result<(), E::Error> {
+        encoder.encode(item, &mut self.buffer)
+    }
+
+    fn poll_flush_buffer<W>(
+        &mut self,
+        lw: &LocalWaker,
+        writer: &mut W,
+    ) -> Poll<io::Result<()>>
+        where W: AsyncWrite + ?Sized,
+    {
+        let mut written = 0;
+        let mut ret = Ok(());
+        while written < self.buffer.len() {
+            match writer.poll_write(lw, &self.buffer[written..]) {
+                Poll::Ready(Ok(0)) => {
+                    ret = Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write frame to transport",
+                    ));
+                    break;
+                }
+                Poll::Ready(Ok(n)) => written += n,
+                Poll::Ready(Err(e)) => {
+                    ret = Err(e);
+                    break;
+                }
+                Poll::Pending => {
+                    self.buffer.drain(..written);
+                    return Poll::Pending;
+                }
+            }
+        }
+        self.buffer.drain(..written);
+        Poll::Ready(ret)
+    }
+
+    pub(super) fn poll_flush<W, E>(
+        &mut self,
+        lw: &LocalWaker,
+        writer: &mut W,
+    ) -> Poll<Result<(), E>>
+        where W: AsyncWrite + ?Sized,
+              E: From<io::Error>,
+    {
+        try_ready!(self.poll_flush_buffer(lw, writer));
+        try_ready!(writer.poll_flush(lw));
+        Poll::Ready(Ok(()))
+    }
+
+    pub(super) fn poll_close<W, E>(
+        &mut self,
+        lw: &LocalWaker,
+        writer: &mut W,
+    ) -> Poll<Result<(), E>>
+        where W: AsyncWrite + ?Sized,
+              E: From<io::Error>,
+    {
+        try_ready!(self.poll_flush::<W, E>(lw, writer));
+        try_ready!(writer.poll_close(lw));
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite, E: Encoder> FramedWrite<W, E> {
+    /// Creates a new `FramedWrite` encoding frames into `inner` with
+    /// `encoder`.
+    pub fn new(inner: W, encoder: E) -> Self {
+        FramedWrite { inner, encoder, state: WriteFrame::default() }
+    }
+}
+
+impl<W, E> FramedWrite<W, E> {
+    /// Returns a reference to the underlying writer.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// writer as it may corrupt the sequence of frames otherwise being worked
+    /// with.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// Note that care should be taken to not tamper with the underlying
+    /// writer as it may corrupt the sequence of frames otherwise being worked
+    /// with.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Consumes the `FramedWrite`, returning its underlying writer.
+    ///
+    /// Note that any encoded frames that have not yet been flushed are lost.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Returns a reference to the encoder.
+    pub fn encoder(&self) -> &E {
+        &self.encoder
+    }
+
+    /// Returns a mutable reference to the encoder.
+    pub fn encoder_mut(&mut self) -> &mut E {
+        &mut self.encoder
+    }
+
+    /// Returns the encoded bytes that have not yet been written out.
+    pub fn write_buffer(&self) -> &[u8] {
+        self.state.buffer()
+    }
+}
+
+impl<W: AsyncWrite, E: Encoder> Sink for FramedWrite<W, E> {
+    type SinkItem = E::Item;
+    type SinkError = E::Error;
+
+    fn poll_ready(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        let this = &mut *self;
+        this.state.poll_ready(lw, &mut this.inner)
+    }
+
+    fn start_send(mut self: Pin<&mut Self>, item: Self::SinkItem) -> Result<(), Self::SinkError> {
+        let this = &mut *self;
+        this.state.start_send(&mut this.encoder, item)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        let this = &mut *self;
+        this.state.poll_flush(lw, &mut this.inner)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Result<(), Self::SinkError>> {
+        let this = &mut *self;
+        this.state.poll_close(lw, &mut this.inner)
+    }
+}
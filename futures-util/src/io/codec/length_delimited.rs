@@ -0,0 +1,95 @@
+use super::{Decoder, Encoder};
+use std::io;
+use std::vec::Vec;
+
+// Size of the big-endian length prefix of each frame.
+const HEADER_LEN: usize = 4;
+
+const DEFAULT_MAX_FRAME_LENGTH: usize = 8 * 1024 * 1024;
+
+/// A codec for frames prefixed by their length.
+///
+/// Each frame is preceded by a 4 byte big-endian unsigned integer holding
+/// the length of the frame's payload, not including the length prefix
+/// itself. Decoded frames are the payloads, without the prefix.
+///
+/// Frames longer than [`max_frame_length`](LengthDelimitedCodec::max_frame_length)
+/// are rejected with an error when decoding as well as when encoding, which
+/// prevents a peer from making the decoder buffer an arbitrary amount of
+/// data.
+#[derive(Debug, Clone)]
+pub struct LengthDelimitedCodec {
+    max_frame_length: usize,
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a new `LengthDelimitedCodec` with the default maximum frame
+    /// length of 8 MB.
+    pub fn new() -> LengthDelimitedCodec {
+        LengthDelimitedCodec {
+            max_frame_length: DEFAULT_MAX_FRAME_LENGTH,
+        }
+    }
+
+    /// Returns the maximum frame length accepted by this codec.
+    pub fn max_frame_length(&self) -> usize {
+        self.max_frame_length
+    }
+
+    /// Sets the maximum frame length accepted by this codec.
+    pub fn set_max_frame_length(&mut self, val: usize) {
+        self.max_frame_length = val;
+    }
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        LengthDelimitedCodec::new()
+    }
+}
+
+fn frame_too_big(kind: io::ErrorKind) -> io::Error {
+    io::Error::new(kind, "frame size too big")
+}
+
+impl Decoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<Vec<u8>>> {
+        if src.len() < HEADER_LEN {
+            return Ok(None);
+        }
+        let len = (u32::from(src[0]) << 24
+            | u32::from(src[1]) << 16
+            | u32::from(src[2]) << 8
+            | u32::from(src[3])) as usize;
+        if len > self.max_frame_length {
+            return Err(frame_too_big(io::ErrorKind::InvalidData));
+        }
+        if src.len() < HEADER_LEN + len {
+            src.reserve(HEADER_LEN + len - src.len());
+            return Ok(None);
+        }
+        let frame = src[HEADER_LEN..HEADER_LEN + len].to_vec();
+        src.drain(..HEADER_LEN + len);
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder for LengthDelimitedCodec {
+    type Item = Vec<u8>;
+    type Error = io::Error;
+
+    fn encode(&mut self, data: Vec<u8>, dst: &mut Vec<u8>) -> io::Result<()> {
+        let len = data.len();
+        if len > self.max_frame_length || len > u32::max_value() as usize {
+            return Err(frame_too_big(io::ErrorKind::InvalidInput));
+        }
+        let len = len as u32;
+        dst.reserve(HEADER_LEN + data.len());
+        dst.extend_from_slice(&[(len >> 24) as u8, (len >> 16) as u8, (len >> 8) as u8, len as u8]);
+        dst.extend_from_slice(&data);
+        Ok(())
+    }
+}
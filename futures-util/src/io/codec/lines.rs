@@ -0,0 +1,81 @@
+use super::{Decoder, Encoder};
+use std::io;
+use std::string::String;
+use std::vec::Vec;
+
+/// A codec for frames delimited by a newline (the 0xA byte).
+///
+/// Decoded lines do not include the trailing `\n` or `\r\n`. Encoding a
+/// line appends a `\n` to it. A final line without a trailing newline is
+/// still yielded when the underlying reader reaches EOF.
+///
+/// Decoding fails with an error of kind `InvalidData` if a line is not valid
+/// UTF-8.
+#[derive(Debug, Clone, Default)]
+pub struct LinesCodec {
+    // The number of bytes at the start of the buffer that are known not to
+    // contain a newline, so they aren't searched again.
+    next_index: usize,
+}
+
+impl LinesCodec {
+    /// Creates a new `LinesCodec`.
+    pub fn new() -> LinesCodec {
+        LinesCodec { next_index: 0 }
+    }
+}
+
+fn utf8(buf: Vec<u8>) -> io::Result<String> {
+    String::from_utf8(buf).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+    })
+}
+
+impl Decoder for LinesCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut Vec<u8>) -> io::Result<Option<String>> {
+        let newline = src[self.next_index..].iter().position(|b| *b == b'\n');
+        match newline {
+            Some(offset) => {
+                let newline_index = self.next_index + offset;
+                self.next_index = 0;
+                let mut line: Vec<u8> = src.drain(..=newline_index).collect();
+                line.pop();
+                if line.ends_with(b"\r") {
+                    line.pop();
+                }
+                utf8(line).map(Some)
+            }
+            None => {
+                self.next_index = src.len();
+                Ok(None)
+            }
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut Vec<u8>) -> io::Result<Option<String>> {
+        match self.decode(src)? {
+            Some(line) => Ok(Some(line)),
+            None if src.is_empty() => Ok(None),
+            None => {
+                self.next_index = 0;
+                let line: Vec<u8> = src.drain(..).collect();
+                utf8(line).map(Some)
+            }
+        }
+    }
+}
+
+impl Encoder for LinesCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn encode(&mut self, line: String, dst: &mut Vec<u8>) -> io::Result<()> {
+        dst.reserve(line.len() + 1);
+        dst.extend_from_slice(line.as_bytes());
+        dst.push(b'\n');
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+//! Adapters between byte-oriented I/O objects and streams and sinks of
+//! frames.
+//!
+//! A [`Decoder`] splits bytes read from an `AsyncRead` into frames, and an
+//! [`Encoder`] turns frames into bytes to be written to an `AsyncWrite`.
+//! [`FramedRead`], [`FramedWrite`] and [`Framed`] combine the two to produce
+//! a `TryStream` of frames and/or a `Sink` of frames.
+
+use std::io;
+use std::vec::Vec;
+
+mod framed;
+pub use self::framed::Framed;
+
+mod framed_read;
+pub use self::framed_read::FramedRead;
+
+mod framed_write;
+pub use self::framed_write::FramedWrite;
+
+mod length_delimited;
+pub use self::length_delimited::LengthDelimitedCodec;
+
+mod lines;
+pub use self::lines::LinesCodec;
+
+// Initial capacity of the read buffer, and the amount by which it grows
+// whenever more data is needed.
+const INITIAL_CAPACITY: usize = 8 * 1024;
+
+// Number of bytes buffered by a `FramedWrite` or `Framed` after which
+// `poll_ready` starts flushing before accepting more frames.
+const BACKPRESSURE_BOUNDARY: usize = INITIAL_CAPACITY;
+
+/// Decoding of frames from bytes, used by [`FramedRead`] and [`Framed`].
+pub trait Decoder {
+    /// The type of decoded frames.
+    type Item;
+
+    /// The type of unrecoverable frame decoding errors.
+    ///
+    /// If an individual message is ill-formed but can be ignored without
+    /// interfering with the processing of future messages, it may be more
+    /// useful to report the failure as an `Item`.
+    ///
+    /// `From<io::Error>` is required so that I/O errors from the underlying
+    /// reader can be reported through the same error type.
+    type Error: From<io::Error>;
+
+    /// Attempts to decode a frame from the provided buffer of bytes.
+    ///
+    /// This method is called by [`FramedRead`] whenever bytes are ready to be
+    /// parsed. The provided buffer contains all the bytes that have been read
+    /// so far and not yet consumed by a previous call.
+    ///
+    /// If the bytes look valid, but a frame isn't fully available yet, then
+    /// `Ok(None)` is returned. This indicates that more data needs to be read
+    /// before decoding can continue.
+    ///
+    /// If a full frame is available, the bytes which make up the frame must be
+    /// removed from the front of `src` (for example with `src.drain(..n)`) and
+    /// `Ok(Some(frame))` returned.
+    ///
+    /// Finally, if the bytes in the buffer are malformed then an error is
+    /// returned, which terminates the stream.
+    fn decode(&mut self, src: &mut Vec<u8>) -> Result<Option<Self::Item>, Self::Error>;
+
+    /// A default method available to be called when there are no more bytes
+    /// available to be read from the underlying I/O object.
+    ///
+    /// This method defaults to calling `decode` and returns an error if
+    /// `Ok(None)` is returned while there is unconsumed data in `src`.
+    /// Typically this doesn't need to be implemented unless the framing
+    /// protocol differs near the end of the stream.
+    fn decode_eof(&mut self, src: &mut Vec<u8>) -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None => {
+                if src.is_empty() {
+                    Ok(None)
+                } else {
+                    Err(io::Error::new(io::ErrorKind::Other, "bytes remaining on stream").into())
+                }
+            }
+        }
+    }
+}
+
+/// Encoding of frames into bytes, used by [`FramedWrite`] and [`Framed`].
+pub trait Encoder {
+    /// The type of items consumed by `encode`.
+    type Item;
+
+    /// The type of encoding errors.
+    ///
+    /// `From<io::Error>` is required so that I/O errors from the underlying
+    /// writer can be reported through the same error type.
+    type Error: From<io::Error>;
+
+    /// Encodes a frame by appending its bytes to the provided buffer.
+    ///
+    /// This method will be called by [`FramedWrite`] for each item passed to
+    /// `start_send`. The buffer is written out to the underlying writer when
+    /// the sink is flushed, or when it grows past an internal limit.
+    fn encode(&mut self, item: Self::Item, dst: &mut Vec<u8>) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,186 @@
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, Initializer, IoVec, SeekFrom};
+use std::boxed::Box;
+use std::io;
+use std::vec::Vec;
+
+/// A `Cursor` wraps an in-memory buffer and provides it with a
+/// [`AsyncSeek`] implementation.
+///
+/// `Cursor`s are used with in-memory buffers, anything implementing
+/// `AsRef<[u8]>`, to allow them to implement [`AsyncRead`] and/or [`AsyncWrite`],
+/// allowing these buffers to be used anywhere you might use a reader or writer
+/// that does actual I/O.
+///
+/// This is the asynchronous counterpart to `std::io::Cursor`. Like it, all
+/// operations complete immediately and never return `Pending`; use
+/// [`pipe`](super::pipe) to exercise code paths that have to wait for data.
+///
+/// [`AsyncSeek`]: futures_io::AsyncSeek
+/// [`AsyncRead`]: futures_io::AsyncRead
+/// [`AsyncWrite`]: futures_io::AsyncWrite
+#[derive(Clone, Debug, Default)]
+pub struct Cursor<T> {
+    inner: io::Cursor<T>,
+}
+
+impl<T> Cursor<T> {
+    /// Creates a new cursor wrapping the provided underlying in-memory buffer.
+    ///
+    /// Cursor initial position is `0` even if underlying buffer (e.g., `Vec`)
+    /// is not empty. So writing to cursor starts with overwriting `Vec`
+    /// content, not with appending to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::io::Cursor;
+    ///
+    /// let buff = Cursor::new(Vec::new());
+    /// # fn force_inference(_: &Cursor<Vec<u8>>) {}
+    /// # force_inference(&buff);
+    /// ```
+    pub fn new(inner: T) -> Cursor<T> {
+        Cursor {
+            inner: io::Cursor::new(inner),
+        }
+    }
+
+    /// Consumes this cursor, returning the underlying value.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    /// Gets a reference to the underlying value in this cursor.
+    pub fn get_ref(&self) -> &T {
+        self.inner.get_ref()
+    }
+
+    /// Gets a mutable reference to the underlying value in this cursor.
+    ///
+    /// Care should be taken to avoid modifying the internal I/O state of the
+    /// underlying value as it may corrupt this cursor's position.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    /// Returns the current position of this cursor.
+    pub fn position(&self) -> u64 {
+        self.inner.position()
+    }
+
+    /// Sets the position of this cursor.
+    pub fn set_position(&mut self, pos: u64) {
+        self.inner.set_position(pos)
+    }
+}
+
+impl<T> AsyncSeek for Cursor<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn poll_seek(&mut self, _: &LocalWaker, pos: SeekFrom)
+        -> Poll<io::Result<u64>>
+    {
+        Poll::Ready(io::Seek::seek(&mut self.inner, pos))
+    }
+}
+
+impl<T: AsRef<[u8]>> AsyncRead for Cursor<T> {
+    unsafe fn initializer(&self) -> Initializer {
+        Initializer::nop()
+    }
+
+    fn poll_read(&mut self, _: &LocalWaker, buf: &mut [u8])
+        -> Poll<io::Result<usize>>
+    {
+        Poll::Ready(io::Read::read(&mut self.inner, buf))
+    }
+
+    fn poll_vectored_read(&mut self, _: &LocalWaker, vec: &mut [&mut IoVec])
+        -> Poll<io::Result<usize>>
+    {
+        let mut nread = 0;
+        for buf in vec {
+            let buf: &mut [u8] = buf;
+            let n = match io::Read::read(&mut self.inner, buf) {
+                Ok(n) => n,
+                Err(e) => return Poll::Ready(Err(e)),
+            };
+            nread += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Poll::Ready(Ok(nread))
+    }
+}
+
+impl<T> AsyncBufRead for Cursor<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn poll_fill_buf(&mut self, _: &LocalWaker)
+        -> Poll<io::Result<&[u8]>>
+    {
+        Poll::Ready(io::BufRead::fill_buf(&mut self.inner))
+    }
+
+    fn consume(&mut self, amt: usize) {
+        io::BufRead::consume(&mut self.inner, amt)
+    }
+}
+
+macro_rules! delegate_async_write_to_stdio {
+    () => {
+        fn poll_write(&mut self, _: &LocalWaker, buf: &[u8])
+            -> Poll<io::Result<usize>>
+        {
+            Poll::Ready(io::Write::write(&mut self.inner, buf))
+        }
+
+        fn poll_vectored_write(&mut self, _: &LocalWaker, vec: &[&IoVec])
+            -> Poll<io::Result<usize>>
+        {
+            // Writing to an in-memory buffer can't fail part way through, so
+            // the buffers can be written one after another.
+            let mut nwritten = 0;
+            for buf in vec {
+                let buf: &[u8] = buf;
+                let n = match io::Write::write(&mut self.inner, buf) {
+                    Ok(n) => n,
+                    Err(e) => return Poll::Ready(Err(e)),
+                };
+                nwritten += n;
+                if n < buf.len() {
+                    break;
+                }
+            }
+            Poll::Ready(Ok(nwritten))
+        }
+
+        fn poll_flush(&mut self, _: &LocalWaker) -> Poll<io::Result<()>> {
+            Poll::Ready(io::Write::flush(&mut self.inner))
+        }
+
+        fn poll_close(&mut self, lw: &LocalWaker) -> Poll<io::Result<()>> {
+            self.poll_flush(lw)
+        }
+    }
+}
+
+impl AsyncWrite for Cursor<&mut [u8]> {
+    delegate_async_write_to_stdio!();
+}
+
+impl AsyncWrite for Cursor<&mut Vec<u8>> {
+    delegate_async_write_to_stdio!();
+}
+
+impl AsyncWrite for Cursor<Vec<u8>> {
+    delegate_async_write_to_stdio!();
+}
+
+impl AsyncWrite for Cursor<Box<[u8]>> {
+    delegate_async_write_to_stdio!();
+}
@@ -0,0 +1,67 @@
+use super::read_line::invalid_utf8;
+use super::read_until::read_until_internal;
+use futures_core::stream::Stream;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::AsyncBufRead;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::string::String;
+use std::vec::Vec;
+
+/// A stream over the lines of text in an I/O object.
+///
+/// Created by the [`lines`] method.
+///
+/// [`lines`]: super::AsyncBufReadExt::lines
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct Lines<R> {
+    reader: R,
+    buf: Vec<u8>,
+    read: usize,
+}
+
+// Pinning is never projected to fields
+impl<R> Unpin for Lines<R> {}
+
+impl<R: AsyncBufRead> Lines<R> {
+    pub(super) fn new(reader: R) -> Self {
+        Lines { reader, buf: Vec::new(), read: 0 }
+    }
+
+    /// Returns the underlying I/O object.
+    ///
+    /// Note that this may lose data already read into internal buffers. It's
+    /// recommended to only call this once the stream has reached its end.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead> Stream for Lines<R> {
+    type Item = io::Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let n = match ready!(read_until_internal(&mut this.reader, lw, b'\n', &mut this.buf, &mut this.read)) {
+            Ok(n) => n,
+            Err(e) => {
+                this.buf.clear();
+                return Poll::Ready(Some(Err(e)));
+            }
+        };
+        if n == 0 && this.buf.is_empty() {
+            return Poll::Ready(None);
+        }
+        if this.buf.ends_with(b"\n") {
+            this.buf.pop();
+            if this.buf.ends_with(b"\r") {
+                this.buf.pop();
+            }
+        }
+        let line = String::from_utf8(mem::replace(&mut this.buf, Vec::new()))
+            .map_err(|_| invalid_utf8());
+        Poll::Ready(Some(line))
+    }
+}
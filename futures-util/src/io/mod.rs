@@ -1,43 +1,78 @@
 //! IO
 //!
 //! This module contains a number of functions for working with
-//! `AsyncRead` and `AsyncWrite` types, including the
-//! `AsyncReadExt` and `AsyncWriteExt` traits which add methods
-//! to the `AsyncRead` and `AsyncWrite` types.
+//! `AsyncRead`, `AsyncWrite`, `AsyncSeek` and `AsyncBufRead` types, including
+//! the `AsyncReadExt`, `AsyncWriteExt`, `AsyncSeekExt` and `AsyncBufReadExt`
+//! traits which add methods to the `AsyncRead`, `AsyncWrite`, `AsyncSeek` and
+//! `AsyncBufRead` types.
 
+use std::string::String;
 use std::vec::Vec;
 
-pub use futures_io::{AsyncRead, AsyncWrite, IoVec};
+pub use futures_io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, IoVec, SeekFrom};
 
 #[cfg(feature = "io-compat")] use crate::compat::Compat;
 
-// Temporarily removed until AsyncBufRead is implemented
-// pub use io::lines::{lines, Lines};
-// pub use io::read_until::{read_until, ReadUntil};
-// mod lines;
-// mod read_until;
+// used by `BufReader` and `BufWriter`
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
 
 mod allow_std;
 pub use self::allow_std::AllowStdIo;
 
+mod buf_reader;
+pub use self::buf_reader::BufReader;
+
+mod buf_writer;
+pub use self::buf_writer::{BufWriter, IntoInner, IntoInnerError};
+
+mod codec;
+pub use self::codec::{
+    Decoder, Encoder, Framed, FramedRead, FramedWrite, LengthDelimitedCodec,
+    LinesCodec,
+};
+
 mod copy_into;
 pub use self::copy_into::CopyInto;
 
+mod cursor;
+pub use self::cursor::Cursor;
+
 mod flush;
 pub use self::flush::Flush;
 
+mod lines;
+pub use self::lines::Lines;
+
+mod pipe;
+pub use self::pipe::{duplex, pipe, Duplex, PipeReader, PipeWriter};
+
 mod read;
 pub use self::read::Read;
 
 mod read_exact;
 pub use self::read_exact::ReadExact;
 
+mod read_line;
+pub use self::read_line::ReadLine;
+
 mod read_to_end;
 pub use self::read_to_end::ReadToEnd;
 
+mod read_to_string;
+pub use self::read_to_string::ReadToString;
+
+mod read_until;
+pub use self::read_until::ReadUntil;
+
+mod read_vectored;
+pub use self::read_vectored::ReadVectored;
+
 mod close;
 pub use self::close::Close;
 
+mod seek;
+pub use self::seek::Seek;
+
 mod split;
 pub use self::split::{ReadHalf, WriteHalf};
 
@@ -47,6 +82,9 @@ pub use self::window::Window;
 mod write_all;
 pub use self::write_all::WriteAll;
 
+mod write_vectored;
+pub use self::write_vectored::WriteVectored;
+
 /// An extension trait which adds utility methods to `AsyncRead` types.
 pub trait AsyncReadExt: AsyncRead {
     /// Creates a future which copies all the bytes from one object to another.
@@ -114,6 +152,43 @@ pub trait AsyncReadExt: AsyncRead {
         Read::new(self, buf)
     }
 
+    /// Creates a future which will read from the `AsyncRead` into `bufs` using
+    /// vectored IO operations.
+    ///
+    /// The returned future will resolve to the number of bytes read once the read
+    /// operation is completed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, Cursor, IoVec};
+    ///
+    /// let mut reader = Cursor::new([1, 2, 3, 4]);
+    /// let mut buf1 = [0u8; 3];
+    /// let mut buf2 = [0u8; 3];
+    ///
+    /// let bytes = {
+    ///     let mut bufs = [
+    ///         IoVec::from_bytes_mut(&mut buf1).unwrap(),
+    ///         IoVec::from_bytes_mut(&mut buf2).unwrap(),
+    ///     ];
+    ///     await!(reader.read_vectored(&mut bufs))?
+    /// };
+    ///
+    /// assert_eq!(bytes, 4);
+    /// assert_eq!(buf1, [1, 2, 3]);
+    /// assert_eq!(buf2, [4, 0, 0]);
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn read_vectored<'a, 'b, 'c>(
+        &'a mut self,
+        bufs: &'b mut [&'c mut IoVec],
+    ) -> ReadVectored<'a, 'b, 'c, Self> {
+        ReadVectored::new(self, bufs)
+    }
+
     /// Creates a future which will read exactly enough bytes to fill `buf`,
     /// returning an error if end of file (EOF) is hit sooner.
     ///
@@ -187,6 +262,36 @@ pub trait AsyncReadExt: AsyncRead {
         ReadToEnd::new(self, buf)
     }
 
+    /// Creates a future which will read all the bytes from this `AsyncRead`
+    /// and append them to `buf`, returning the number of bytes read.
+    ///
+    /// If the data read is not valid UTF-8, the future resolves to an error
+    /// of kind `InvalidData` and `buf` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::AsyncReadExt;
+    /// use std::io::Cursor;
+    ///
+    /// let mut reader = Cursor::new(&b"1234"[..]);
+    /// let mut buffer = String::with_capacity(4);
+    ///
+    /// let bytes = await!(reader.read_to_string(&mut buffer))?;
+    ///
+    /// assert_eq!(bytes, 4);
+    /// assert_eq!(buffer, String::from("1234"));
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn read_to_string<'a>(
+        &'a mut self,
+        buf: &'a mut String,
+    ) -> ReadToString<'a, Self> {
+        ReadToString::new(self, buf)
+    }
+
     /// Helper method for splitting this read/write object into two halves.
     ///
     /// The two halves returned implement the `AsyncRead` and `AsyncWrite`
@@ -300,6 +405,37 @@ pub trait AsyncWriteExt: AsyncWrite {
         WriteAll::new(self, buf)
     }
 
+    /// Creates a future which will write bytes from `bufs` into the object
+    /// using vectored IO operations.
+    ///
+    /// The returned future will resolve to the number of bytes written once
+    /// the write operation is completed. Like
+    /// [`poll_vectored_write`](futures_io::AsyncWrite::poll_vectored_write),
+    /// this may be fewer than the total length of `bufs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncWriteExt, Cursor, IoVec};
+    ///
+    /// let mut writer = Cursor::new(Vec::new());
+    /// let bufs = [
+    ///     IoVec::from_bytes(&[1, 2]).unwrap(),
+    ///     IoVec::from_bytes(&[3, 4]).unwrap(),
+    /// ];
+    ///
+    /// let bytes = await!(writer.write_vectored(&bufs))?;
+    ///
+    /// assert_eq!(bytes, 4);
+    /// assert_eq!(writer.into_inner(), [1, 2, 3, 4]);
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn write_vectored<'a>(&'a mut self, bufs: &'a [&'a IoVec]) -> WriteVectored<'a, Self> {
+        WriteVectored::new(self, bufs)
+    }
+
     /// Wraps an [`AsyncWrite`] in a compatibility wrapper that allows it to be
     /// used as a futures 0.1 / tokio-io 0.1 `AsyncWrite`.
     /// Requires the `io-compat` feature to enable.
@@ -312,3 +448,184 @@ pub trait AsyncWriteExt: AsyncWrite {
 }
 
 impl<W: AsyncWrite + ?Sized> AsyncWriteExt for W {}
+
+/// An extension trait which adds utility methods to `AsyncSeek` types.
+pub trait AsyncSeekExt: AsyncSeek {
+    /// Creates a future which will seek an IO object, and then yield the
+    /// new position in the object.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+    /// use std::io::Cursor;
+    ///
+    /// let mut reader = Cursor::new([1, 2, 3, 4]);
+    /// let mut output = [0u8; 2];
+    ///
+    /// let pos = await!(reader.seek(SeekFrom::End(-2)))?;
+    /// assert_eq!(pos, 2);
+    ///
+    /// await!(reader.read_exact(&mut output))?;
+    /// assert_eq!(output, [3, 4]);
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn seek(&mut self, pos: SeekFrom) -> Seek<'_, Self> {
+        Seek::new(self, pos)
+    }
+}
+
+impl<S: AsyncSeek + ?Sized> AsyncSeekExt for S {}
+
+/// An extension trait which adds utility methods to `AsyncBufRead` types.
+pub trait AsyncBufReadExt: AsyncBufRead {
+    /// Creates a future which will read all the bytes associated with this I/O
+    /// object into `buf` until the delimiter `byte` or EOF is reached.
+    /// This method is the async equivalent to [`BufRead::read_until`](std::io::BufRead::read_until).
+    ///
+    /// This function will read bytes from the underlying stream until the
+    /// delimiter or EOF is found. Once found, all bytes up to, and including,
+    /// the delimiter (if found) will be appended to `buf`.
+    ///
+    /// The returned future will resolve to the number of bytes read once the read
+    /// operation is completed.
+    ///
+    /// If an error occurs the future resolves to it. Any bytes read before
+    /// the error have already been appended to `buf`, and remain there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::AsyncBufReadExt;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"lorem-ipsum");
+    /// let mut buf = vec![];
+    ///
+    /// // cursor is at 'l'
+    /// let num_bytes = await!(cursor.read_until(b'-', &mut buf))?;
+    /// assert_eq!(num_bytes, 6);
+    /// assert_eq!(buf, b"lorem-");
+    /// buf.clear();
+    ///
+    /// // cursor is at 'i'
+    /// let num_bytes = await!(cursor.read_until(b'-', &mut buf))?;
+    /// assert_eq!(num_bytes, 5);
+    /// assert_eq!(buf, b"ipsum");
+    /// buf.clear();
+    ///
+    /// // cursor is at EOF
+    /// let num_bytes = await!(cursor.read_until(b'-', &mut buf))?;
+    /// assert_eq!(num_bytes, 0);
+    /// assert_eq!(buf, b"");
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn read_until<'a>(
+        &'a mut self,
+        byte: u8,
+        buf: &'a mut Vec<u8>,
+    ) -> ReadUntil<'a, Self> {
+        ReadUntil::new(self, byte, buf)
+    }
+
+    /// Creates a future which will read all the bytes associated with this I/O
+    /// object into `buf` until a newline (the 0xA byte) or EOF is reached.
+    /// This method is the async equivalent to [`BufRead::read_line`](std::io::BufRead::read_line).
+    ///
+    /// This function will read bytes from the underlying stream until the
+    /// newline delimiter (the 0xA byte) or EOF is found. Once found, all bytes
+    /// up to, and including, the delimiter (if found) will be appended to
+    /// `buf`.
+    ///
+    /// The returned future will resolve to the number of bytes read once the read
+    /// operation is completed.
+    ///
+    /// # Errors
+    ///
+    /// This function has the same error semantics as [`read_until`] and will
+    /// also return an error if the read bytes are not valid UTF-8. If an I/O
+    /// error is encountered then `buf` may contain some bytes already read in
+    /// the event that all data read so far was valid UTF-8. If the bytes read
+    /// are not valid UTF-8, `buf` is left unchanged.
+    ///
+    /// [`read_until`]: AsyncBufReadExt::read_until
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::AsyncBufReadExt;
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(b"foo\nbar");
+    /// let mut buf = String::new();
+    ///
+    /// // cursor is at 'f'
+    /// let num_bytes = await!(cursor.read_line(&mut buf))?;
+    /// assert_eq!(num_bytes, 4);
+    /// assert_eq!(buf, "foo\n");
+    /// buf.clear();
+    ///
+    /// // cursor is at 'b'
+    /// let num_bytes = await!(cursor.read_line(&mut buf))?;
+    /// assert_eq!(num_bytes, 3);
+    /// assert_eq!(buf, "bar");
+    /// buf.clear();
+    ///
+    /// // cursor is at EOF
+    /// let num_bytes = await!(cursor.read_line(&mut buf))?;
+    /// assert_eq!(num_bytes, 0);
+    /// assert_eq!(buf, "");
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn read_line<'a>(&'a mut self, buf: &'a mut String) -> ReadLine<'a, Self> {
+        ReadLine::new(self, buf)
+    }
+
+    /// Returns a stream over the lines of this reader.
+    /// This method is the async equivalent to [`BufRead::lines`](std::io::BufRead::lines).
+    ///
+    /// The stream returned from this function will yield instances of
+    /// [`io::Result`]`<`[`String`]`>`. Each string returned will *not* have a newline
+    /// byte (the 0xA byte) or CRLF (0xD, 0xA bytes) at the end.
+    ///
+    /// [`io::Result`]: std::io::Result
+    /// [`String`]: String
+    ///
+    /// # Errors
+    ///
+    /// Each line of the stream has the same error semantics as [`AsyncBufReadExt::read_line`].
+    ///
+    /// [`AsyncBufReadExt::read_line`]: AsyncBufReadExt::read_line
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(async_await, await_macro, futures_api)]
+    /// # futures::executor::block_on(async {
+    /// use futures::io::AsyncBufReadExt;
+    /// use futures::stream::StreamExt;
+    /// use std::io::Cursor;
+    ///
+    /// let cursor = Cursor::new(b"lorem\nipsum\r\ndolor");
+    ///
+    /// let mut lines_stream = cursor.lines().map(|l| l.unwrap());
+    /// assert_eq!(await!(lines_stream.next()), Some(String::from("lorem")));
+    /// assert_eq!(await!(lines_stream.next()), Some(String::from("ipsum")));
+    /// assert_eq!(await!(lines_stream.next()), Some(String::from("dolor")));
+    /// assert_eq!(await!(lines_stream.next()), None);
+    /// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+    /// ```
+    fn lines(self) -> Lines<Self>
+        where Self: Sized,
+    {
+        Lines::new(self)
+    }
+}
+
+impl<R: AsyncBufRead + ?Sized> AsyncBufReadExt for R {}
@@ -0,0 +1,254 @@
+use futures_core::task::{LocalWaker, Poll, Waker};
+use futures_io::{AsyncRead, AsyncWrite, IoVec};
+use std::cmp;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex};
+
+/// Creates a new in-memory pipe with room for `capacity` bytes, returning
+/// the writing and reading halves.
+///
+/// Unlike a [`Cursor`](super::Cursor), reading from an empty pipe returns
+/// `Pending` until the [`PipeWriter`] writes more data or is closed, and
+/// writing to a full pipe returns `Pending` until the [`PipeReader`] makes
+/// room. This makes pipes useful for exercising the code paths of I/O
+/// consumers that have to wait for readiness.
+///
+/// Once the writer has been closed or dropped, reads return the remaining
+/// data and then EOF. Writes fail with `BrokenPipe` once the reader has been
+/// dropped.
+///
+/// # Panics
+///
+/// Panics if `capacity` is `0`.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(async_await, await_macro, futures_api)]
+/// # futures::executor::block_on(async {
+/// use futures::io::{pipe, AsyncReadExt, AsyncWriteExt};
+///
+/// let (mut writer, mut reader) = pipe(4);
+///
+/// await!(writer.write_all(&[1, 2, 3]))?;
+/// await!(writer.close())?;
+///
+/// let mut output = Vec::new();
+/// await!(reader.read_to_end(&mut output))?;
+/// assert_eq!(output, vec![1, 2, 3]);
+/// # Ok::<(), Box<std::error::Error>>(()) }).unwrap();
+/// ```
+pub fn pipe(capacity: usize) -> (PipeWriter, PipeReader) {
+    assert!(capacity > 0, "pipe capacity must be greater than zero");
+    let inner = Arc::new(Mutex::new(Inner {
+        buf: VecDeque::with_capacity(capacity),
+        capacity,
+        read_task: None,
+        write_task: None,
+        reader_dropped: false,
+        writer_closed: false,
+    }));
+    (PipeWriter { inner: inner.clone() }, PipeReader { inner })
+}
+
+/// Creates a pair of connected in-memory streams, each with a buffer of
+/// `capacity` bytes in either direction.
+///
+/// Data written to one [`Duplex`] can be read from the other. Each direction
+/// behaves like a [`pipe`].
+///
+/// # Panics
+///
+/// Panics if `capacity` is `0`.
+pub fn duplex(capacity: usize) -> (Duplex, Duplex) {
+    let (a_writer, b_reader) = pipe(capacity);
+    let (b_writer, a_reader) = pipe(capacity);
+    (
+        Duplex { reader: a_reader, writer: a_writer },
+        Duplex { reader: b_reader, writer: b_writer },
+    )
+}
+
+#[derive(Debug)]
+struct Inner {
+    buf: VecDeque<u8>,
+    capacity: usize,
+    read_task: Option<Waker>,
+    write_task: Option<Waker>,
+    reader_dropped: bool,
+    writer_closed: bool,
+}
+
+fn wake(task: &mut Option<Waker>) {
+    if let Some(task) = task.take() {
+        task.wake();
+    }
+}
+
+/// The reading half of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeReader {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// The writing half of a [`pipe`].
+#[derive(Debug)]
+pub struct PipeWriter {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl AsyncRead for PipeReader {
+    fn poll_read(&mut self, lw: &LocalWaker, buf: &mut [u8])
+        -> Poll<io::Result<usize>>
+    {
+        let mut inner = self.inner.lock().unwrap();
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        if inner.buf.is_empty() {
+            if inner.writer_closed {
+                return Poll::Ready(Ok(0));
+            }
+            inner.read_task = Some(lw.clone().into_waker());
+            return Poll::Pending;
+        }
+
+        let n = cmp::min(buf.len(), inner.buf.len());
+        for (dst, src) in buf.iter_mut().zip(inner.buf.drain(..n)) {
+            *dst = src;
+        }
+        wake(&mut inner.write_task);
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl Drop for PipeReader {
+    fn drop(&mut self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.reader_dropped = true;
+        wake(&mut inner.write_task);
+    }
+}
+
+impl PipeWriter {
+    fn poll_write_with<F>(&mut self, lw: &LocalWaker, f: F)
+        -> Poll<io::Result<usize>>
+        where F: FnOnce(&mut VecDeque<u8>, usize) -> usize,
+    {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.reader_dropped {
+            return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into()));
+        }
+        if inner.writer_closed {
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Other,
+                "write to a closed pipe",
+            )));
+        }
+        let available = inner.capacity - inner.buf.len();
+        if available == 0 {
+            inner.write_task = Some(lw.clone().into_waker());
+            return Poll::Pending;
+        }
+
+        let n = f(&mut inner.buf, available);
+        if n > 0 {
+            wake(&mut inner.read_task);
+        }
+        Poll::Ready(Ok(n))
+    }
+}
+
+impl AsyncWrite for PipeWriter {
+    fn poll_write(&mut self, lw: &LocalWaker, buf: &[u8])
+        -> Poll<io::Result<usize>>
+    {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        self.poll_write_with(lw, |dst, available| {
+            let n = cmp::min(buf.len(), available);
+            dst.extend(&buf[..n]);
+            n
+        })
+    }
+
+    fn poll_vectored_write(&mut self, lw: &LocalWaker, vec: &[&IoVec])
+        -> Poll<io::Result<usize>>
+    {
+        if vec.iter().all(|buf| buf.is_empty()) {
+            return Poll::Ready(Ok(0));
+        }
+        self.poll_write_with(lw, |dst, mut available| {
+            let mut nwritten = 0;
+            for buf in vec {
+                let n = cmp::min(buf.len(), available);
+                dst.extend(&buf[..n]);
+                nwritten += n;
+                available -= n;
+                if available == 0 {
+                    break;
+                }
+            }
+            nwritten
+        })
+    }
+
+    fn poll_flush(&mut self, _: &LocalWaker) -> Poll<io::Result<()>> {
+        // Written data is immediately visible to the reader.
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(&mut self, _: &LocalWaker) -> Poll<io::Result<()>> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.writer_closed = true;
+        wake(&mut inner.read_task);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for PipeWriter {
+    fn drop(&mut self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.writer_closed = true;
+        wake(&mut inner.read_task);
+    }
+}
+
+/// One end of a bidirectional in-memory stream created by [`duplex`].
+#[derive(Debug)]
+pub struct Duplex {
+    reader: PipeReader,
+    writer: PipeWriter,
+}
+
+impl AsyncRead for Duplex {
+    fn poll_read(&mut self, lw: &LocalWaker, buf: &mut [u8])
+        -> Poll<io::Result<usize>>
+    {
+        self.reader.poll_read(lw, buf)
+    }
+}
+
+impl AsyncWrite for Duplex {
+    fn poll_write(&mut self, lw: &LocalWaker, buf: &[u8])
+        -> Poll<io::Result<usize>>
+    {
+        self.writer.poll_write(lw, buf)
+    }
+
+    fn poll_vectored_write(&mut self, lw: &LocalWaker, vec: &[&IoVec])
+        -> Poll<io::Result<usize>>
+    {
+        self.writer.poll_vectored_write(lw, vec)
+    }
+
+    fn poll_flush(&mut self, lw: &LocalWaker) -> Poll<io::Result<()>> {
+        self.writer.poll_flush(lw)
+    }
+
+    fn poll_close(&mut self, lw: &LocalWaker) -> Poll<io::Result<()>> {
+        self.writer.poll_close(lw)
+    }
+}
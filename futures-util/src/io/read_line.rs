@@ -0,0 +1,54 @@
+use super::read_until::read_until_internal;
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::AsyncBufRead;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::string::String;
+use std::vec::Vec;
+
+/// A future which can be used to easily read the contents of a stream into a
+/// string until a newline is reached.
+///
+/// Created by the [`read_line`] method.
+///
+/// [`read_line`]: super::AsyncBufReadExt::read_line
+#[derive(Debug)]
+pub struct ReadLine<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut String,
+    bytes: Vec<u8>,
+    read: usize,
+}
+
+// Pinning is never projected to fields
+impl<R: ?Sized> Unpin for ReadLine<'_, R> {}
+
+impl<'a, R: AsyncBufRead + ?Sized> ReadLine<'a, R> {
+    pub(super) fn new(reader: &'a mut R, buf: &'a mut String) -> Self {
+        ReadLine { reader, buf, bytes: Vec::new(), read: 0 }
+    }
+}
+
+pub(super) fn invalid_utf8() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")
+}
+
+impl<R: AsyncBufRead + ?Sized> Future for ReadLine<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let ret = ready!(read_until_internal(this.reader, lw, b'\n', &mut this.bytes, &mut this.read));
+        // Like `std`, the bytes read are only appended to `buf` if they are
+        // valid UTF-8, otherwise `buf` is left untouched.
+        match String::from_utf8(mem::replace(&mut this.bytes, Vec::new())) {
+            Ok(line) => {
+                this.buf.push_str(&line);
+                Poll::Ready(ret)
+            }
+            Err(_) => Poll::Ready(ret.and_then(|_| Err(invalid_utf8()))),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use super::read_to_end::read_to_end_internal;
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::AsyncRead;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::string::String;
+use std::vec::Vec;
+
+/// A future which can be used to easily read the entire contents of a stream
+/// into a string.
+///
+/// Created by the [`read_to_string`] method.
+///
+/// [`read_to_string`]: super::AsyncReadExt::read_to_string
+#[derive(Debug)]
+pub struct ReadToString<'a, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut String,
+    bytes: Vec<u8>,
+}
+
+// Pinning is never projected to fields
+impl<R: ?Sized> Unpin for ReadToString<'_, R> {}
+
+impl<'a, R: AsyncRead + ?Sized> ReadToString<'a, R> {
+    pub(super) fn new(reader: &'a mut R, buf: &'a mut String) -> Self {
+        ReadToString { reader, buf, bytes: Vec::new() }
+    }
+}
+
+impl<A> Future for ReadToString<'_, A>
+    where A: AsyncRead + ?Sized,
+{
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let ret = ready!(read_to_end_internal(this.reader, lw, &mut this.bytes));
+        // Like `std`, the bytes read are only appended to `buf` if they are
+        // valid UTF-8, otherwise `buf` is left untouched.
+        match String::from_utf8(mem::replace(&mut this.bytes, Vec::new())) {
+            Ok(s) => {
+                this.buf.push_str(&s);
+                Poll::Ready(ret.map(|()| s.len()))
+            }
+            Err(_) => Poll::Ready(ret.and_then(|()| Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )))),
+        }
+    }
+}
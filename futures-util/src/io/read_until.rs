@@ -0,0 +1,76 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::AsyncBufRead;
+use std::io;
+use std::mem;
+use std::pin::Pin;
+use std::vec::Vec;
+
+/// A future which can be used to easily read the contents of a stream into a
+/// vector until the delimiter is reached.
+///
+/// Created by the [`read_until`] method.
+///
+/// [`read_until`]: super::AsyncBufReadExt::read_until
+#[derive(Debug)]
+pub struct ReadUntil<'a, R: ?Sized> {
+    reader: &'a mut R,
+    byte: u8,
+    buf: &'a mut Vec<u8>,
+    read: usize,
+}
+
+// Pinning is never projected to fields
+impl<R: ?Sized> Unpin for ReadUntil<'_, R> {}
+
+impl<'a, R: AsyncBufRead + ?Sized> ReadUntil<'a, R> {
+    pub(super) fn new(reader: &'a mut R, byte: u8, buf: &'a mut Vec<u8>) -> Self {
+        ReadUntil { reader, byte, buf, read: 0 }
+    }
+}
+
+// Reads from `reader` into `buf` until `byte` or EOF is hit. `read` keeps
+// track of the number of bytes appended to `buf` across calls that returned
+// `Pending`, and is reset once the read completes or fails.
+pub(super) fn read_until_internal<R: AsyncBufRead + ?Sized>(
+    reader: &mut R,
+    lw: &LocalWaker,
+    byte: u8,
+    buf: &mut Vec<u8>,
+    read: &mut usize,
+) -> Poll<io::Result<usize>> {
+    loop {
+        let (done, used) = {
+            let available = match reader.poll_fill_buf(lw) {
+                Poll::Ready(Ok(available)) => available,
+                Poll::Ready(Err(e)) => {
+                    // The read is over, so start counting afresh next time.
+                    *read = 0;
+                    return Poll::Ready(Err(e));
+                }
+                Poll::Pending => return Poll::Pending,
+            };
+            if let Some(i) = available.iter().position(|b| *b == byte) {
+                buf.extend_from_slice(&available[..=i]);
+                (true, i + 1)
+            } else {
+                buf.extend_from_slice(available);
+                (false, available.len())
+            }
+        };
+        reader.consume(used);
+        *read += used;
+        if done || used == 0 {
+            return Poll::Ready(Ok(mem::replace(read, 0)));
+        }
+    }
+}
+
+impl<R: AsyncBufRead + ?Sized> Future for ReadUntil<'_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        read_until_internal(this.reader, lw, this.byte, this.buf, &mut this.read)
+    }
+}
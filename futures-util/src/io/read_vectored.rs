@@ -0,0 +1,36 @@
+use crate::io::AsyncRead;
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::IoVec;
+use std::io;
+use std::pin::Pin;
+
+/// A future which can be used to easily read available number of bytes to fill
+/// a set of buffers.
+///
+/// Created by the [`read_vectored`] method.
+///
+/// [`read_vectored`]: super::AsyncReadExt::read_vectored
+#[derive(Debug)]
+pub struct ReadVectored<'a, 'b, 'c, R: ?Sized> {
+    reader: &'a mut R,
+    bufs: &'b mut [&'c mut IoVec],
+}
+
+// Pinning is never projected to fields
+impl<R: ?Sized> Unpin for ReadVectored<'_, '_, '_, R> {}
+
+impl<'a, 'b, 'c, R: AsyncRead + ?Sized> ReadVectored<'a, 'b, 'c, R> {
+    pub(super) fn new(reader: &'a mut R, bufs: &'b mut [&'c mut IoVec]) -> Self {
+        ReadVectored { reader, bufs }
+    }
+}
+
+impl<R: AsyncRead + ?Sized> Future for ReadVectored<'_, '_, '_, R> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.reader.poll_vectored_read(lw, this.bufs)
+    }
+}
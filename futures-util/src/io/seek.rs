@@ -0,0 +1,30 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::{AsyncSeek, SeekFrom};
+use std::io;
+use std::pin::Pin;
+
+/// Future for the [`seek`](super::AsyncSeekExt::seek) method.
+#[derive(Debug)]
+pub struct Seek<'a, S: ?Sized> {
+    seek: &'a mut S,
+    pos: SeekFrom,
+}
+
+// Pinning is never projected to fields
+impl<S: ?Sized> Unpin for Seek<'_, S> {}
+
+impl<'a, S: AsyncSeek + ?Sized> Seek<'a, S> {
+    pub(super) fn new(seek: &'a mut S, pos: SeekFrom) -> Self {
+        Seek { seek, pos }
+    }
+}
+
+impl<S: AsyncSeek + ?Sized> Future for Seek<'_, S> {
+    type Output = io::Result<u64>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.seek.poll_seek(lw, this.pos)
+    }
+}
@@ -0,0 +1,35 @@
+use futures_core::future::Future;
+use futures_core::task::{LocalWaker, Poll};
+use futures_io::{AsyncWrite, IoVec};
+use std::io;
+use std::pin::Pin;
+
+/// A future which can be used to write bytes from a set of buffers into an
+/// object using a single vectored write.
+///
+/// Created by the [`write_vectored`] method.
+///
+/// [`write_vectored`]: super::AsyncWriteExt::write_vectored
+#[derive(Debug)]
+pub struct WriteVectored<'a, W: ?Sized> {
+    writer: &'a mut W,
+    bufs: &'a [&'a IoVec],
+}
+
+// Pinning is never projected to fields
+impl<W: ?Sized> Unpin for WriteVectored<'_, W> {}
+
+impl<'a, W: AsyncWrite + ?Sized> WriteVectored<'a, W> {
+    pub(super) fn new(writer: &'a mut W, bufs: &'a [&'a IoVec]) -> Self {
+        WriteVectored { writer, bufs }
+    }
+}
+
+impl<W: AsyncWrite + ?Sized> Future for WriteVectored<'_, W> {
+    type Output = io::Result<usize>;
+
+    fn poll(mut self: Pin<&mut Self>, lw: &LocalWaker) -> Poll<Self::Output> {
+        let this = &mut *self;
+        this.writer.poll_vectored_write(lw, this.bufs)
+    }
+}
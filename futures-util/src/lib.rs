@@ -89,7 +89,7 @@ pub mod compat;
 #[cfg(feature = "std")]
 pub mod io;
 #[cfg(feature = "std")]
-#[doc(hidden)] pub use crate::io::{AsyncReadExt, AsyncWriteExt};
+#[doc(hidden)] pub use crate::io::{AsyncReadExt, AsyncWriteExt, AsyncSeekExt, AsyncBufReadExt};
 
 #[cfg(feature = "std")]
 pub mod lock;
@@ -61,20 +61,34 @@ pub use futures_util::{
     join, try_join, pending, poll,
 };
 
-#[cfg(feature = "std")]
 pub mod channel {
     //! Cross-task communication.
     //!
     //! Like threads, concurrent tasks sometimes need to communicate with each
-    //! other. This module contains two basic abstractions for doing so:
+    //! other. This module contains several basic abstractions for doing so:
     //!
     //! - [oneshot](crate::channel::oneshot), a way of sending a single value
     //!   from one task to another.
     //! - [mpsc](crate::channel::mpsc), a multi-producer, single-consumer
     //!   channel for sending values between tasks, analogous to the
     //!   similarly-named structure in the standard library.
+    //! - [mpmc](crate::channel::mpmc), a bounded multi-producer,
+    //!   multi-consumer queue where each value is taken by a single receiver.
+    //! - [broadcast](crate::channel::broadcast), a multi-producer,
+    //!   multi-consumer channel where every receiver observes every value.
+    //! - [watch](crate::channel::watch), a channel which only retains the
+    //!   latest value and notifies receivers whenever it changes.
+    //! - [oneshot_slot](crate::channel::oneshot_slot), a oneshot channel
+    //!   whose state lives in caller-provided storage, usable without `std`.
+
+    #[cfg(feature = "std")]
+    pub use futures_channel::{oneshot, mpsc, mpmc, broadcast, watch};
 
-    pub use futures_channel::{oneshot, mpsc};
+    #[cfg_attr(
+        feature = "cfg-target-has-atomic",
+        cfg(all(target_has_atomic = "cas", target_has_atomic = "ptr"))
+    )]
+    pub use futures_channel::oneshot_slot;
 }
 
 #[cfg(feature = "compat")]
@@ -148,14 +162,23 @@ pub mod executor {
     //! [`block_on`](crate::executor::block_on), for simply running a future to
     //! completion on the current thread, while routing any spawned tasks
     //! to a global thread pool.
+    //!
+    //! # Timers
+    //!
+    //! The [`timer`](crate::executor::timer) module provides futures and
+    //! streams which complete after some time has passed, along with timeout
+    //! combinators and a mockable clock for tests.
 
     pub use futures_executor::{
         BlockingStream,
         Enter, EnterError,
-        LocalSpawner, LocalPool,
-        ThreadPool, ThreadPoolBuilder,
+        JoinError, JoinHandle,
+        LocalSpawner, LocalPool, Metrics,
+        Shutdown, ThreadPool, ThreadPoolBuilder,
         block_on, block_on_stream, enter,
     };
+
+    pub use futures_executor::timer;
 }
 
 pub mod future {
@@ -182,6 +205,7 @@ pub mod future {
         maybe_done, MaybeDone,
         poll_fn, PollFn,
         ready, ok, err, Ready,
+        yield_now, YieldNow,
 
         OptionFuture,
 
@@ -215,26 +239,37 @@ pub mod future {
 pub mod io {
     //! Asynchronous I/O.
     //!
-    //! This module is the asynchronous version of `std::io`. It defines two
-    //! traits, [`AsyncRead`](crate::io::AsyncRead) and
-    //! [`AsyncWrite`](crate::io::AsyncWrite), which mirror the `Read` and
-    //! `Write` traits of the standard library. However, these traits integrate
+    //! This module is the asynchronous version of `std::io`. It defines four
+    //! traits, [`AsyncRead`](crate::io::AsyncRead),
+    //! [`AsyncWrite`](crate::io::AsyncWrite),
+    //! [`AsyncSeek`](crate::io::AsyncSeek) and
+    //! [`AsyncBufRead`](crate::io::AsyncBufRead), which mirror the `Read`,
+    //! `Write`, `Seek` and `BufRead` traits of the standard library. However, these traits integrate
     //! with the asynchronous task system, so that if an I/O object isn't ready
     //! for reading (or writing), the thread is not blocked, and instead the
     //! current task is queued to be woken when I/O is ready.
     //!
-    //! In addition, the [`AsyncReadExt`](crate::io::AsyncReadExt) and
-    //! [`AsyncWriteExt`](crate::io::AsyncWriteExt) extension traits offer a
+    //! In addition, the [`AsyncReadExt`](crate::io::AsyncReadExt),
+    //! [`AsyncWriteExt`](crate::io::AsyncWriteExt),
+    //! [`AsyncSeekExt`](crate::io::AsyncSeekExt) and
+    //! [`AsyncBufReadExt`](crate::io::AsyncBufReadExt) extension traits offer a
     //! variety of useful combinators for operating with asynchronous I/O
     //! objects, including ways to work with them using futures, streams and
     //! sinks.
 
     pub use futures_io::{
-        Error, Initializer, IoVec, ErrorKind, AsyncRead, AsyncWrite, Result
+        Error, Initializer, IoVec, ErrorKind, AsyncBufRead, AsyncRead,
+        AsyncSeek, AsyncWrite, Result, SeekFrom,
     };
     pub use futures_util::io::{
-        AsyncReadExt, AsyncWriteExt, AllowStdIo, Close, CopyInto, Flush,
-        Read, ReadExact, ReadHalf, ReadToEnd, Window, WriteAll, WriteHalf,
+        AsyncReadExt, AsyncWriteExt, AsyncSeekExt, AsyncBufReadExt, AllowStdIo,
+        BufReader, BufWriter, Close, CopyInto, Cursor, Duplex, Flush, IntoInner,
+        IntoInnerError, Lines, PipeReader, PipeWriter, Read, ReadExact,
+        ReadHalf, ReadLine, ReadToEnd, ReadToString, ReadUntil, ReadVectored,
+        Seek, Window, WriteAll, WriteHalf, WriteVectored, duplex, pipe,
+
+        Decoder, Encoder, Framed, FramedRead, FramedWrite, LengthDelimitedCodec,
+        LinesCodec,
     };
 }
 
@@ -262,7 +297,10 @@ pub mod prelude {
     pub use crate::sink::{self, Sink, SinkExt};
 
     #[cfg(feature = "std")]
-    pub use crate::io::{ AsyncRead, AsyncWrite, AsyncReadExt, AsyncWriteExt };
+    pub use crate::io::{
+        AsyncRead, AsyncWrite, AsyncSeek, AsyncBufRead,
+        AsyncReadExt, AsyncWriteExt, AsyncSeekExt, AsyncBufReadExt,
+    };
 }
 
 pub mod sink {
@@ -0,0 +1,100 @@
+#![feature(futures_api)]
+
+use futures::executor::block_on;
+use futures::io::{AllowStdIo, AsyncBufRead, AsyncRead, AsyncReadExt, AsyncSeekExt, BufReader, SeekFrom};
+use futures::task::Poll;
+use futures_test::task::noop_local_waker_ref;
+use std::io::Cursor;
+
+#[test]
+fn test_buffered_reader() {
+    let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
+    let mut reader = BufReader::with_capacity(2, inner);
+
+    let mut buf = [0, 0, 0];
+    let nread = block_on(reader.read(&mut buf));
+    assert_eq!(nread.unwrap(), 3);
+    assert_eq!(buf, [5, 6, 7]);
+    assert_eq!(reader.buffer(), []);
+
+    let mut buf = [0, 0];
+    let nread = block_on(reader.read(&mut buf));
+    assert_eq!(nread.unwrap(), 2);
+    assert_eq!(buf, [0, 1]);
+    assert_eq!(reader.buffer(), []);
+
+    let mut buf = [0];
+    let nread = block_on(reader.read(&mut buf));
+    assert_eq!(nread.unwrap(), 1);
+    assert_eq!(buf, [2]);
+    assert_eq!(reader.buffer(), [3]);
+
+    let mut buf = [0, 0, 0];
+    let nread = block_on(reader.read(&mut buf));
+    assert_eq!(nread.unwrap(), 1);
+    assert_eq!(buf, [3, 0, 0]);
+    assert_eq!(reader.buffer(), []);
+
+    let nread = block_on(reader.read(&mut buf));
+    assert_eq!(nread.unwrap(), 1);
+    assert_eq!(buf, [4, 0, 0]);
+    assert_eq!(reader.buffer(), []);
+
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 0);
+}
+
+#[test]
+fn test_buffered_reader_fill_buf_and_consume() {
+    let lw = noop_local_waker_ref();
+    let inner: &[u8] = &[1, 2, 3, 4, 5, 6];
+    let mut reader = BufReader::with_capacity(4, inner);
+
+    match reader.poll_fill_buf(lw) {
+        Poll::Ready(Ok(buf)) => assert_eq!(buf, [1, 2, 3, 4]),
+        other => panic!("unexpected {:?}", other),
+    }
+    reader.consume(3);
+    match reader.poll_fill_buf(lw) {
+        Poll::Ready(Ok(buf)) => assert_eq!(buf, [4]),
+        other => panic!("unexpected {:?}", other),
+    }
+    reader.consume(1);
+    match reader.poll_fill_buf(lw) {
+        Poll::Ready(Ok(buf)) => assert_eq!(buf, [5, 6]),
+        other => panic!("unexpected {:?}", other),
+    }
+    reader.consume(2);
+    match reader.poll_fill_buf(lw) {
+        Poll::Ready(Ok(buf)) => assert_eq!(buf, []),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    let mut buf = [0];
+    match reader.poll_read(lw, &mut buf) {
+        Poll::Ready(Ok(0)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_buffered_reader_seek() {
+    let inner: &[u8] = &[5, 6, 7, 0, 1, 2, 3, 4];
+    let mut reader = BufReader::with_capacity(2, AllowStdIo::new(Cursor::new(inner)));
+
+    assert_eq!(block_on(reader.seek(SeekFrom::Start(3))).unwrap(), 3);
+    let mut buf = [0];
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 1);
+    assert_eq!(buf, [0]);
+    assert_eq!(reader.buffer(), [1]);
+
+    // `Current` is relative to the logical position, not the inner reader.
+    assert_eq!(block_on(reader.seek(SeekFrom::Current(1))).unwrap(), 5);
+    assert_eq!(reader.buffer(), []);
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 1);
+    assert_eq!(buf, [2]);
+
+    assert_eq!(block_on(reader.seek(SeekFrom::End(-1))).unwrap(), 7);
+    assert_eq!(block_on(reader.read(&mut buf)).unwrap(), 1);
+    assert_eq!(buf, [4]);
+    assert_eq!(reader.get_ref().get_ref().position(), 8);
+}
@@ -0,0 +1,82 @@
+#![feature(futures_api)]
+
+use futures::executor::block_on;
+use futures::io::{AllowStdIo, AsyncWrite, AsyncWriteExt, BufWriter};
+use futures::task::{LocalWaker, Poll};
+use std::io;
+
+#[test]
+fn buf_writer() {
+    let mut writer = BufWriter::with_capacity(2, AllowStdIo::new(Vec::new()));
+
+    block_on(writer.write_all(&[0, 1])).unwrap();
+    assert_eq!(writer.buffer(), []);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1]);
+
+    block_on(writer.write_all(&[2])).unwrap();
+    assert_eq!(writer.buffer(), [2]);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1]);
+
+    block_on(writer.write_all(&[3])).unwrap();
+    assert_eq!(writer.buffer(), [2, 3]);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1]);
+
+    block_on(writer.flush()).unwrap();
+    assert_eq!(writer.buffer(), []);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1, 2, 3]);
+
+    block_on(writer.write_all(&[4])).unwrap();
+    block_on(writer.write_all(&[5])).unwrap();
+    assert_eq!(writer.buffer(), [4, 5]);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1, 2, 3]);
+
+    block_on(writer.write_all(&[6])).unwrap();
+    assert_eq!(writer.buffer(), [6]);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1, 2, 3, 4, 5]);
+
+    block_on(writer.write_all(&[7, 8])).unwrap();
+    assert_eq!(writer.buffer(), []);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+    block_on(writer.write_all(&[9, 10, 11])).unwrap();
+    assert_eq!(writer.buffer(), []);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+
+    block_on(writer.flush()).unwrap();
+    assert_eq!(writer.buffer(), []);
+    assert_eq!(*writer.get_ref().get_ref(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+}
+
+#[test]
+fn buf_writer_into_inner_flushes() {
+    let mut writer = BufWriter::with_capacity(4, AllowStdIo::new(Vec::new()));
+    block_on(writer.write_all(&[0, 1, 2])).unwrap();
+    assert_eq!(*writer.get_ref().get_ref(), []);
+    let inner = block_on(writer.into_inner()).unwrap();
+    assert_eq!(inner.into_inner(), [0, 1, 2]);
+}
+
+struct FailingWriter;
+
+impl AsyncWrite for FailingWriter {
+    fn poll_write(&mut self, _: &LocalWaker, _: &[u8]) -> Poll<io::Result<usize>> {
+        Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, "nope")))
+    }
+
+    fn poll_flush(&mut self, _: &LocalWaker) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(&mut self, _: &LocalWaker) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[test]
+fn buf_writer_into_inner_error_returns_buffer() {
+    let mut writer = BufWriter::with_capacity(4, FailingWriter);
+    block_on(writer.write_all(&[0, 1, 2])).unwrap();
+    let err = block_on(writer.into_inner()).unwrap_err();
+    assert_eq!(err.error().kind(), io::ErrorKind::Other);
+    assert_eq!(err.into_inner().buffer(), [0, 1, 2]);
+}
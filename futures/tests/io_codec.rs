@@ -0,0 +1,86 @@
+#![feature(async_await, await_macro, futures_api)]
+
+use futures::executor::{block_on, block_on_stream};
+use futures::io::{
+    pipe, AsyncWriteExt, Cursor, ErrorKind, Framed, FramedRead, FramedWrite,
+    LengthDelimitedCodec, LinesCodec,
+};
+use futures::sink::SinkExt;
+use futures::stream::StreamExt;
+
+#[test]
+fn framed_read_lines() {
+    let input = Cursor::new(&b"hello\r\nworld\n\nlast"[..]);
+    let lines = block_on_stream(FramedRead::new(input, LinesCodec::new()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(lines, vec!["hello", "world", "", "last"]);
+}
+
+#[test]
+fn framed_read_lines_across_reads() {
+    let (mut writer, reader) = pipe(3);
+    let mut stream = FramedRead::new(reader, LinesCodec::new());
+
+    block_on(async {
+        // The pipe only holds 3 bytes, so the line arrives in pieces.
+        let write = async move {
+            await!(writer.write_all(b"abcdef\ngh\n")).unwrap();
+        };
+        let read = async {
+            let a = await!(stream.next()).unwrap().unwrap();
+            let b = await!(stream.next()).unwrap().unwrap();
+            assert!(await!(stream.next()).is_none());
+            (a, b)
+        };
+        let ((), (a, b)) = futures::join!(write, read);
+        assert_eq!(a, "abcdef");
+        assert_eq!(b, "gh");
+    });
+}
+
+#[test]
+fn framed_read_invalid_utf8() {
+    let input = Cursor::new(&b"\xff\n"[..]);
+    let mut stream = block_on_stream(FramedRead::new(input, LinesCodec::new()));
+    assert_eq!(stream.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert!(stream.next().is_none());
+}
+
+#[test]
+fn framed_write_length_delimited() {
+    let mut sink = FramedWrite::new(Cursor::new(Vec::new()), LengthDelimitedCodec::new());
+    block_on(sink.send(vec![1, 2, 3])).unwrap();
+    block_on(sink.send(vec![])).unwrap();
+    assert_eq!(sink.into_inner().into_inner(), vec![0, 0, 0, 3, 1, 2, 3, 0, 0, 0, 0]);
+}
+
+#[test]
+fn length_delimited_round_trip() {
+    let mut framed = Framed::new(Cursor::new(Vec::new()), LengthDelimitedCodec::new());
+    block_on(framed.send(vec![4, 5])).unwrap();
+    block_on(framed.send(vec![6])).unwrap();
+
+    let mut io = framed.into_inner();
+    io.set_position(0);
+    let frames = block_on_stream(FramedRead::new(io, LengthDelimitedCodec::new()))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(frames, vec![vec![4, 5], vec![6]]);
+}
+
+#[test]
+fn length_delimited_frame_too_big() {
+    let mut codec = LengthDelimitedCodec::new();
+    codec.set_max_frame_length(2);
+    let input = Cursor::new(&[0, 0, 0, 3, 1, 2, 3][..]);
+    let mut stream = block_on_stream(FramedRead::new(input, codec));
+    assert_eq!(stream.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn length_delimited_truncated_frame() {
+    let input = Cursor::new(&[0, 0, 0, 3, 1][..]);
+    let mut stream = block_on_stream(FramedRead::new(input, LengthDelimitedCodec::new()));
+    assert_eq!(stream.next().unwrap().unwrap_err().kind(), ErrorKind::Other);
+}
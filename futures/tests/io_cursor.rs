@@ -0,0 +1,32 @@
+#![feature(futures_api)]
+
+use futures::executor::block_on;
+use futures::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, Cursor, SeekFrom};
+
+#[test]
+fn cursor_read_write_seek() {
+    let mut cursor = Cursor::new(Vec::new());
+
+    block_on(cursor.write_all(&[1, 2, 3, 4])).unwrap();
+    assert_eq!(cursor.position(), 4);
+
+    assert_eq!(block_on(cursor.seek(SeekFrom::Start(1))).unwrap(), 1);
+    let mut buf = [0u8; 2];
+    block_on(cursor.read_exact(&mut buf)).unwrap();
+    assert_eq!(buf, [2, 3]);
+
+    // Writing past the end of a `Vec` grows it.
+    assert_eq!(block_on(cursor.seek(SeekFrom::End(0))).unwrap(), 4);
+    block_on(cursor.write_all(&[5, 6])).unwrap();
+    assert_eq!(cursor.into_inner(), [1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn cursor_slice_write_is_bounded() {
+    let mut buf = [0u8; 3];
+    {
+        let mut cursor = Cursor::new(&mut buf[..]);
+        assert!(block_on(cursor.write_all(&[1, 2, 3, 4])).is_err());
+    }
+    assert_eq!(buf, [1, 2, 3]);
+}
@@ -0,0 +1,138 @@
+#![feature(futures_api)]
+
+use futures::executor::{block_on, block_on_stream};
+use futures::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, BufReader, Error, ErrorKind};
+use futures::task::{LocalWaker, Poll};
+use std::cmp;
+use std::io::Cursor;
+
+#[test]
+fn read_until() {
+    let mut buf = Cursor::new(&b"12"[..]);
+    let mut v = Vec::new();
+    assert_eq!(block_on(buf.read_until(b'3', &mut v)).unwrap(), 2);
+    assert_eq!(v, b"12");
+
+    let mut buf = Cursor::new(&b"1233"[..]);
+    let mut v = Vec::new();
+    assert_eq!(block_on(buf.read_until(b'3', &mut v)).unwrap(), 3);
+    assert_eq!(v, b"123");
+    v.truncate(0);
+    assert_eq!(block_on(buf.read_until(b'3', &mut v)).unwrap(), 1);
+    assert_eq!(v, b"3");
+    v.truncate(0);
+    assert_eq!(block_on(buf.read_until(b'3', &mut v)).unwrap(), 0);
+    assert_eq!(v, []);
+}
+
+#[test]
+fn read_until_across_buffer_boundaries() {
+    let inner: &[u8] = b"abcdefgh|ij";
+    let mut reader = BufReader::with_capacity(3, inner);
+    let mut v = Vec::new();
+    assert_eq!(block_on(reader.read_until(b'|', &mut v)).unwrap(), 9);
+    assert_eq!(v, b"abcdefgh|");
+}
+
+#[test]
+fn read_line() {
+    let mut buf = Cursor::new(&b"12"[..]);
+    let mut v = String::new();
+    assert_eq!(block_on(buf.read_line(&mut v)).unwrap(), 2);
+    assert_eq!(v, "12");
+
+    let mut buf = Cursor::new(&b"12\n\n"[..]);
+    let mut v = String::new();
+    assert_eq!(block_on(buf.read_line(&mut v)).unwrap(), 3);
+    assert_eq!(v, "12\n");
+    v.truncate(0);
+    assert_eq!(block_on(buf.read_line(&mut v)).unwrap(), 1);
+    assert_eq!(v, "\n");
+    v.truncate(0);
+    assert_eq!(block_on(buf.read_line(&mut v)).unwrap(), 0);
+    assert_eq!(v, "");
+}
+
+#[test]
+fn read_line_invalid_utf8_leaves_buffer_unchanged() {
+    let mut buf = Cursor::new(&b"\xff\xfe\n"[..]);
+    let mut v = String::from("prefix");
+    let err = block_on(buf.read_line(&mut v)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(v, "prefix");
+}
+
+#[test]
+fn lines() {
+    let buf = Cursor::new(&b"12\r"[..]);
+    let mut s = block_on_stream(buf.lines());
+    assert_eq!(s.next().unwrap().unwrap(), "12\r".to_string());
+    assert!(s.next().is_none());
+
+    let buf = Cursor::new(&b"12\r\n\n"[..]);
+    let mut s = block_on_stream(buf.lines());
+    assert_eq!(s.next().unwrap().unwrap(), "12".to_string());
+    assert_eq!(s.next().unwrap().unwrap(), "".to_string());
+    assert!(s.next().is_none());
+}
+
+#[test]
+fn lines_invalid_utf8() {
+    let buf = Cursor::new(&b"ok\n\xff\nok\n"[..]);
+    let mut s = block_on_stream(buf.lines());
+    assert_eq!(s.next().unwrap().unwrap(), "ok".to_string());
+    assert_eq!(s.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(s.next().unwrap().unwrap(), "ok".to_string());
+    assert!(s.next().is_none());
+}
+
+#[test]
+fn lines_error_then_eof() {
+    // Yields a partial line, then an error, then EOF.
+    struct Flaky {
+        chunks: Vec<Option<&'static [u8]>>,
+    }
+
+    impl AsyncRead for Flaky {
+        fn poll_read(&mut self, lw: &LocalWaker, buf: &mut [u8]) -> Poll<Result<usize, Error>> {
+            let n = match self.poll_fill_buf(lw) {
+                Poll::Ready(Ok(chunk)) => {
+                    let n = cmp::min(chunk.len(), buf.len());
+                    buf[..n].copy_from_slice(&chunk[..n]);
+                    n
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            self.consume(n);
+            Poll::Ready(Ok(n))
+        }
+    }
+
+    impl AsyncBufRead for Flaky {
+        fn poll_fill_buf(&mut self, _: &LocalWaker) -> Poll<Result<&[u8], Error>> {
+            match self.chunks.first() {
+                Some(Some(chunk)) => Poll::Ready(Ok(*chunk)),
+                Some(None) => {
+                    self.chunks.remove(0);
+                    Poll::Ready(Err(Error::new(ErrorKind::Other, "flaky")))
+                }
+                None => Poll::Ready(Ok(&[])),
+            }
+        }
+
+        fn consume(&mut self, amt: usize) {
+            if let Some(Some(chunk)) = self.chunks.first_mut() {
+                *chunk = &chunk[amt..];
+                if chunk.is_empty() {
+                    self.chunks.remove(0);
+                }
+            }
+        }
+    }
+
+    let reader = Flaky { chunks: vec![Some(b"ab"), None] };
+    let mut s = block_on_stream(reader.lines());
+    assert_eq!(s.next().unwrap().unwrap_err().kind(), ErrorKind::Other);
+    assert!(s.next().is_none());
+}
@@ -0,0 +1,81 @@
+#![feature(futures_api)]
+
+use futures::executor::block_on;
+use futures::io::{duplex, pipe, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ErrorKind};
+use futures::task::Poll;
+use futures_test::task::{new_count_waker, noop_local_waker_ref};
+
+#[test]
+fn read_pending_until_written() {
+    let (mut writer, mut reader) = pipe(4);
+    let (lw, count) = new_count_waker();
+    let mut buf = [0u8; 4];
+
+    assert!(reader.poll_read(&lw, &mut buf).is_pending());
+    assert_eq!(count, 0);
+
+    block_on(writer.write_all(&[1, 2])).unwrap();
+    assert_eq!(count, 1);
+
+    match reader.poll_read(&lw, &mut buf) {
+        Poll::Ready(Ok(2)) => assert_eq!(buf[..2], [1, 2]),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn write_pending_when_full() {
+    let (mut writer, mut reader) = pipe(2);
+    let (lw, count) = new_count_waker();
+
+    match writer.poll_write(&lw, &[1, 2, 3]) {
+        Poll::Ready(Ok(2)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+    assert!(writer.poll_write(&lw, &[3]).is_pending());
+
+    let mut buf = [0u8; 1];
+    block_on(reader.read_exact(&mut buf)).unwrap();
+    assert_eq!(buf, [1]);
+    assert_eq!(count, 1);
+
+    match writer.poll_write(&lw, &[3]) {
+        Poll::Ready(Ok(1)) => {}
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn eof_after_writer_closed() {
+    let (mut writer, mut reader) = pipe(8);
+    block_on(writer.write_all(&[1, 2, 3])).unwrap();
+    drop(writer);
+
+    let mut out = Vec::new();
+    block_on(reader.read_to_end(&mut out)).unwrap();
+    assert_eq!(out, vec![1, 2, 3]);
+}
+
+#[test]
+fn broken_pipe_after_reader_dropped() {
+    let (mut writer, reader) = pipe(8);
+    drop(reader);
+    match writer.poll_write(noop_local_waker_ref(), &[1]) {
+        Poll::Ready(Err(e)) => assert_eq!(e.kind(), ErrorKind::BrokenPipe),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn duplex_both_directions() {
+    let (mut a, mut b) = duplex(8);
+    let mut buf = [0u8; 2];
+
+    block_on(a.write_all(&[1, 2])).unwrap();
+    block_on(b.read_exact(&mut buf)).unwrap();
+    assert_eq!(buf, [1, 2]);
+
+    block_on(b.write_all(&[3, 4])).unwrap();
+    block_on(a.read_exact(&mut buf)).unwrap();
+    assert_eq!(buf, [3, 4]);
+}
@@ -0,0 +1,23 @@
+#![feature(futures_api)]
+
+use futures::executor::block_on;
+use futures::io::{AsyncReadExt, ErrorKind};
+
+#[test]
+fn read_to_string() {
+    let mut reader: &[u8] = b"hello";
+    let mut buf = String::from("say ");
+    assert_eq!(block_on(reader.read_to_string(&mut buf)).unwrap(), 5);
+    assert_eq!(buf, "say hello");
+    assert_eq!(block_on(reader.read_to_string(&mut buf)).unwrap(), 0);
+    assert_eq!(buf, "say hello");
+}
+
+#[test]
+fn read_to_string_invalid_utf8_leaves_buffer_unchanged() {
+    let mut reader: &[u8] = b"ok\xff";
+    let mut buf = String::from("before");
+    let err = block_on(reader.read_to_string(&mut buf)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(buf, "before");
+}